@@ -1,7 +1,7 @@
 use sp_core::{Pair, Public, sr25519};
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig,
-	SudoConfig, SystemConfig, WASM_BINARY, Signature
+	AccountId, AssetId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig,
+	KittiesConfig, PuppiesConfig, SudoConfig, SystemConfig, WASM_BINARY, Signature
 };
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_finality_grandpa::AuthorityId as GrandpaId;
@@ -11,6 +11,9 @@ use sc_service::ChainType;
 // The URL for the telemetry server.
 // const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
 
+/// Local test asset kitties can be priced in, created at genesis and owned by the sudo account.
+pub const TEST_ASSET_ID: AssetId = 1;
+
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
 pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig>;
 
@@ -151,7 +154,17 @@ fn testnet_genesis(
 		}),
 		pallet_sudo: Some(SudoConfig {
 			// Assign network admin rights.
-			key: root_key,
+			key: root_key.clone(),
+		}),
+		pallet_kitties: Some(KittiesConfig {
+			// Allow pricing kitties in the local test asset.
+			accepted_assets: vec![TEST_ASSET_ID],
+			// pallet-assets has no genesis config, the test asset is created here.
+			genesis_assets: vec![(TEST_ASSET_ID, root_key, 1)],
+		}),
+		pallet_kitties_Instance1: Some(PuppiesConfig {
+			accepted_assets: vec![TEST_ASSET_ID],
+			genesis_assets: vec![],
 		}),
 	}
 }
@@ -0,0 +1,18 @@
+//! Runtime API definition for pallet-kitties.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+pub use pallet_kitties::KittyDetails;
+
+sp_api::decl_runtime_apis! {
+	pub trait KittiesApi<AccountId, KittyIndex> where
+		AccountId: Codec,
+		KittyIndex: Codec,
+	{
+		/// Kitty's owner, DNA and attributes, `None` if the kitty doesn't exist
+		fn kitty_details(kitty_id: KittyIndex) -> Option<KittyDetails<AccountId>>;
+		/// Kitty's rarity score, higher for rarer traits, `None` if the kitty doesn't exist
+		fn kitty_rarity(kitty_id: KittyIndex) -> Option<u32>;
+	}
+}
@@ -0,0 +1,65 @@
+//! RPC interface for pallet-kitties.
+
+use std::sync::Arc;
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+pub use pallet_kitties_rpc_runtime_api::{KittiesApi as KittiesRuntimeApi, KittyDetails};
+
+#[rpc]
+pub trait KittiesApi<BlockHash, AccountId, KittyIndex> {
+	/// Kitty's owner, DNA and attributes
+	#[rpc(name = "kitties_kittyDetails")]
+	fn kitty_details(&self, kitty_id: KittyIndex, at: Option<BlockHash>) -> Result<Option<KittyDetails<AccountId>>>;
+
+	/// Kitty's rarity score
+	#[rpc(name = "kitties_kittyRarity")]
+	fn kitty_rarity(&self, kitty_id: KittyIndex, at: Option<BlockHash>) -> Result<Option<u32>>;
+}
+
+/// Implements the kitties RPC API, querying the runtime
+pub struct Kitties<C, Block> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<Block>,
+}
+
+impl<C, Block> Kitties<C, Block> {
+	pub fn new(client: Arc<C>) -> Self {
+		Kitties { client, _marker: Default::default() }
+	}
+}
+
+/// Error code of runtime API failures
+const RUNTIME_ERROR: i64 = 1;
+
+impl<C, Block, AccountId, KittyIndex> KittiesApi<<Block as BlockT>::Hash, AccountId, KittyIndex> for Kitties<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: KittiesRuntimeApi<Block, AccountId, KittyIndex>,
+	AccountId: Codec,
+	KittyIndex: Codec,
+{
+	fn kitty_details(&self, kitty_id: KittyIndex, at: Option<<Block as BlockT>::Hash>) -> Result<Option<KittyDetails<AccountId>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.kitty_details(&at, kitty_id).map_err(|e| RpcError {
+			code: ErrorCode::ServerError(RUNTIME_ERROR),
+			message: "Unable to query kitty details.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})
+	}
+
+	fn kitty_rarity(&self, kitty_id: KittyIndex, at: Option<<Block as BlockT>::Hash>) -> Result<Option<u32>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.kitty_rarity(&at, kitty_id).map_err(|e| RpcError {
+			code: ErrorCode::ServerError(RUNTIME_ERROR),
+			message: "Unable to query kitty rarity.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})
+	}
+}
@@ -0,0 +1,75 @@
+//! Kitty battles, fought out between the kitties' DNA derived stats and a random value.
+//!
+//! Kept free of storage, so game clients can replay a battle from its events.
+
+use codec::{Encode, Decode};
+use frame_support::RuntimeDebug;
+use sp_std::prelude::*;
+
+/// Hit points kitties start a battle with
+pub const HIT_POINTS: u8 = 100;
+/// Battles still undecided after this many rounds are decided on hit points
+pub const MAX_ROUNDS: usize = 16;
+/// Experience gained by the winner
+pub const WIN_EXPERIENCE: u32 = 10;
+
+/// Fighting stats, each in `1..=16`
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+pub struct Stats {
+	pub attack: u8,
+	pub defence: u8,
+	pub speed: u8,
+}
+
+impl Stats {
+	/// Stats are read off DNA bytes 8 to 10
+	pub fn from_dna(dna: &[u8; 16]) -> Self {
+		Stats {
+			attack: dna[8] % 16 + 1,
+			defence: dna[9] % 16 + 1,
+			speed: dna[10] % 16 + 1,
+		}
+	}
+}
+
+/// A blow landed in a battle
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+pub struct Strike {
+	/// Whether the challenger strikes, otherwise the opponent does
+	pub by_challenger: bool,
+	pub damage: u8,
+	/// Hit points the struck kitty is left with
+	pub hit_points: u8,
+}
+
+/// Outcome of a battle, with the strikes in the order they landed
+#[derive(Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct Outcome {
+	pub challenger_wins: bool,
+	pub strikes: Vec<Strike>,
+}
+
+/// Fight a battle. Each round both kitties strike, the faster one first, with a random byte per round
+/// adding luck to the damage. Ties on speed and hit points go to the challenger
+pub fn fight(challenger: Stats, opponent: Stats, random: [u8; 16]) -> Outcome {
+	let stats = [challenger, opponent];
+	let mut hit_points = [HIT_POINTS; 2];
+	let mut strikes = Vec::new();
+	let first = if opponent.speed > challenger.speed { 1 } else { 0 };
+
+	for luck in random.iter().take(MAX_ROUNDS) {
+		for &attacker in [first, 1 - first].iter() {
+			let defender = 1 - attacker;
+			let damage = (stats[attacker].attack * 2 + luck % 8)
+				.saturating_sub(stats[defender].defence)
+				.max(1);
+			hit_points[defender] = hit_points[defender].saturating_sub(damage);
+			strikes.push(Strike { by_challenger: attacker == 0, damage, hit_points: hit_points[defender] });
+			if hit_points[defender] == 0 {
+				return Outcome { challenger_wins: attacker == 0, strikes };
+			}
+		}
+	}
+
+	Outcome { challenger_wins: hit_points[0] >= hit_points[1], strikes }
+}
@@ -0,0 +1,37 @@
+//! Kitty levels and evolution. Experience earned by battling and breeding levels kitties up,
+//! each level evolves the kitty's DNA.
+
+use codec::{Encode, Decode};
+use frame_support::RuntimeDebug;
+
+/// Experience needed to reach each level, level 0 needs none
+pub const LEVEL_EXPERIENCE: [u32; 5] = [20, 50, 100, 200, 400];
+/// Experience gained by both parents on breeding
+pub const BREED_EXPERIENCE: u32 = 5;
+/// DNA byte recording the evolution stage
+pub const STAGE_BYTE: usize = 11;
+
+/// A kitty's experience and level, kitties start out at level 0
+#[derive(Encode, Decode, Clone, Copy, Default, RuntimeDebug, PartialEq, Eq)]
+pub struct KittyProgress {
+	pub experience: u32,
+	pub level: u8,
+}
+
+/// Level reached with the experience
+pub fn level(experience: u32) -> u8 {
+	LEVEL_EXPERIENCE.iter().filter(|threshold| experience >= **threshold).count() as u8
+}
+
+/// DNA of a kitty evolving to `level`. Levels raise the fighting stats in DNA bytes 8 to 10 in turn,
+/// and the stage is recorded in `STAGE_BYTE`. Genes read by `genes::decode()` are untouched
+pub fn evolve(dna: [u8; 16], level: u8) -> [u8; 16] {
+	let mut dna = dna;
+	let stat = 8 + (level.saturating_sub(1) % 3) as usize;
+	// stats are the byte modulo 16, maxed out at 15
+	if dna[stat] % 16 < 15 {
+		dna[stat] += 1;
+	}
+	dna[STAGE_BYTE] = level;
+	dna
+}
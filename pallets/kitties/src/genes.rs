@@ -0,0 +1,68 @@
+//! Decoding of kitty DNA into named traits.
+//!
+//! Byte 0 of the DNA is the gender, see `Kitty::get_gender()`. Each gene below is read off
+//! one of the following bytes, the gene's value is the byte modulo the gene's number of variants.
+
+use codec::{Encode, Decode};
+use frame_support::RuntimeDebug;
+
+/// A named trait of kitties
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+pub enum Gene {
+	FurColour,
+	Pattern,
+	EyeColour,
+	EyeShape,
+	Tail,
+	Whiskers,
+}
+
+/// All genes, in DNA order
+pub const GENES: [Gene; 6] = [
+	Gene::FurColour,
+	Gene::Pattern,
+	Gene::EyeColour,
+	Gene::EyeShape,
+	Gene::Tail,
+	Gene::Whiskers,
+];
+
+impl Gene {
+	/// DNA byte the gene is read from
+	pub fn byte(self) -> usize {
+		match self {
+			Gene::FurColour => 1,
+			Gene::Pattern => 2,
+			Gene::EyeColour => 3,
+			Gene::EyeShape => 4,
+			Gene::Tail => 5,
+			Gene::Whiskers => 6,
+		}
+	}
+
+	/// Number of distinct values of the gene
+	pub fn variants(self) -> u8 {
+		match self {
+			Gene::FurColour => 8,
+			Gene::Pattern => 4,
+			Gene::EyeColour => 8,
+			Gene::EyeShape => 4,
+			Gene::Tail => 4,
+			Gene::Whiskers => 2,
+		}
+	}
+
+	/// Value of the gene carried by the DNA, in `0..variants()`
+	pub fn decode(self, dna: &[u8; 16]) -> u8 {
+		dna[self.byte()] % self.variants()
+	}
+}
+
+/// All genes with their values carried by the DNA
+pub fn decode(dna: &[u8; 16]) -> [(Gene, u8); 6] {
+	let mut traits = [(Gene::FurColour, 0); 6];
+	for (i, gene) in GENES.iter().enumerate() {
+		traits[i] = (*gene, gene.decode(dna));
+	}
+	traits
+}
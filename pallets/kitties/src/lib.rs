@@ -2,17 +2,31 @@
 
 // mod kitties;
 //
+pub mod traits;
+pub mod genes;
+pub mod battle;
+pub mod evolution;
+
+pub use traits::{KittyRegistry, OnKittyTransfer, FungibleAssets};
+use genes::Gene;
+use battle::{Stats, Strike};
+use evolution::KittyProgress;
+
 use std::fmt;
 use codec::{Encode, Decode};
+#[cfg(feature = "std")]
+use serde::{Serialize, Deserialize};
 use frame_support::{
-	decl_module, decl_storage, decl_event, decl_error, StorageValue, StorageDoubleMap, Parameter,
-	traits::{Randomness, Currency, ExistenceRequirement}, ensure,
-	RuntimeDebug,
+	decl_module, decl_storage, decl_event, decl_error, StorageValue, StorageDoubleMap, IterableStorageDoubleMap, Parameter, transactional,
+	traits::{Randomness, Currency, ReservableCurrency, ExistenceRequirement, BalanceStatus, EnsureOrigin, Get}, ensure,
+	weights::Weight, RuntimeDebug,
 };
 use frame_support::dispatch::{DispatchError, DispatchResult};
+use frame_support::storage::{with_transaction, TransactionOutcome};
 use sp_io::hashing::blake2_128;
-use frame_system::ensure_signed;
-use sp_runtime::traits::{AtLeast32BitUnsigned, Bounded, One, CheckedAdd};
+use frame_system::{ensure_signed, ensure_root};
+use sp_runtime::{ModuleId, Permill, traits::{AccountIdConversion, AtLeast32BitUnsigned, Bounded, One, CheckedAdd, Saturating, Zero}};
+use sp_std::prelude::*;
 
 // #[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
 #[derive(PartialEq, Eq, RuntimeDebug)]
@@ -33,89 +47,642 @@ impl Kitty {
     }
 }
 
-pub trait Config: pallet_balances::Config {
-	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
-	type Currency: Currency<Self::AccountId>;
+/// How batch calls handle failing items
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+pub enum BatchMode {
+	/// Any failing item fails the whole batch
+	AllOrNothing,
+	/// Failing items are skipped, the others go through
+	BestEffort,
+}
+
+/// Storage layout versions, for migrations
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Releases {
+	V1,
+	/// Kitties minted before the owner and DNA indexes are indexed in `KittyOwner` and `DnaKitties`,
+	/// and counted in `TraitCounts` and `KittyCount`
+	V2,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1
+	}
+}
+
+/// A pending birth, recorded against the momma kitty until the `due` block
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct Pregnancy<AccountId, KittyIndex, BlockNumber> {
+	/// Breeder, receives the kitten
+	pub owner: AccountId,
+	/// Id reserved for the kitten at conception
+	pub child_id: KittyIndex,
+	pub papa_id: KittyIndex,
+	/// Parents' DNA as at conception
+	pub momma: Kitty,
+	pub papa: Kitty,
+	pub due: BlockNumber,
+}
+
+/// A male kitty offered for siring until the `expires` block
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct SiringOffer<AccountId, Balance, BlockNumber> {
+	pub owner: AccountId,
+	/// Stud fee, paid to the owner per breeding
+	pub fee: Balance,
+	pub expires: BlockNumber,
+}
+
+/// A named collection of kitties, with its own index space
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct Collection<AccountId> {
+	pub owner: AccountId,
+	pub name: Vec<u8>,
+	/// Maximum number of kitties minted into the collection, unlimited if `None`
+	pub max_supply: Option<u32>,
+	/// Number of kitties minted so far, also the next kitty index within the collection
+	pub minted: u32,
+	/// Share of every sale paid to the collection owner
+	pub royalty: Permill,
+	/// Whether the collection's kitties can change hands
+	pub transferable: bool,
+}
+
+/// A kitty attribute, with the deposit reserved for it
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct Attribute<AccountId, Balance> {
+	pub value: Vec<u8>,
+	/// Account the deposit is reserved from, refunded to on clearing
+	pub depositor: AccountId,
+	pub deposit: Balance,
+}
+
+/// A kitty name, with the deposit reserved for it
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct KittyName<AccountId, Balance> {
+	pub name: Vec<u8>,
+	/// Account the deposit is reserved from, refunded to on clearing
+	pub depositor: AccountId,
+	pub deposit: Balance,
+}
+
+/// Off-chain metadata of a kitty or collection
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct Metadata<Hash> {
+	pub uri: Vec<u8>,
+	/// Hash of the content at `uri`
+	pub hash: Hash,
+	/// Frozen metadata can never change
+	pub frozen: bool,
+}
+
+/// A kitty's challenge to fight another kitty, the wager is reserved from the challenger
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct Challenge<AccountId, KittyIndex, Balance> {
+	pub owner: AccountId,
+	pub opponent: KittyIndex,
+	/// Matched by the opponent on acceptance, both go to the winner
+	pub wager: Balance,
+}
+
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+pub enum TournamentStatus {
+	/// Kitties can enter until the deadline
+	Registration,
+	/// A round is fought every block, until a single kitty is left
+	InProgress,
+	Finished,
+	/// Too few entrants by the deadline, entry fees are refunded
+	Cancelled,
+}
+
+/// A knockout tournament, entry fees make up the prize pool
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct Tournament<AccountId, Balance, BlockNumber, KittyIndex> {
+	pub entry_fee: Balance,
+	/// Registration closes and the first round is fought at this block
+	pub deadline: BlockNumber,
+	/// Maximum number of entrants, a power of two
+	pub bracket_size: u32,
+	/// Entered kitties, with the accounts that paid their entry fee
+	pub entrants: Vec<(KittyIndex, AccountId)>,
+	/// Kitties yet to be knocked out
+	pub remaining: Vec<KittyIndex>,
+	/// Next round to be fought
+	pub round: u32,
+	pub status: TournamentStatus,
+	pub winner: Option<KittyIndex>,
+}
+
+/// A kitty transfer awaiting the recipient's acceptance until the `expires` block
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct TransferOffer<AccountId, BlockNumber> {
+	pub owner: AccountId,
+	pub recipient: AccountId,
+	pub expires: BlockNumber,
+}
+
+/// An offer to lend a kitty to `borrower` for `duration` blocks
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct LendOffer<AccountId, Balance, BlockNumber> {
+	pub owner: AccountId,
+	pub borrower: AccountId,
+	pub duration: BlockNumber,
+	/// Paid by the borrower to the owner
+	pub fee: Balance,
+}
+
+/// A lent kitty, the borrower has usage rights for breeding and battles until the `expires` block
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct Rental<AccountId, BlockNumber> {
+	pub owner: AccountId,
+	pub borrower: AccountId,
+	pub expires: BlockNumber,
+}
+
+/// A loan against a kitty as collateral, requested until a lender funds it
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct Loan<AccountId, Balance, BlockNumber> {
+	/// Kitty's owner
+	pub borrower: AccountId,
+	/// `None` until funded, the kitty is locked from then on
+	pub lender: Option<AccountId>,
+	pub amount: Balance,
+	/// Paid on top of `amount` on repayment
+	pub interest: Balance,
+	/// Last block to repay in, the lender can claim the kitty after
+	pub due: BlockNumber,
+}
+
+/// Kitty details, as served by the runtime API
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct KittyDetails<AccountId> {
+	pub owner: AccountId,
+	pub dna: [u8; 16],
+	pub name: Option<Vec<u8>>,
+	/// Attribute keys and values
+	pub attributes: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+pub trait Config<I: Instance = DefaultInstance>: frame_system::Config {
+	type Event: From<Event<Self, I>> + Into<<Self as frame_system::Config>::Event>;
+	type Currency: ReservableCurrency<Self::AccountId>;
 	type Randomness: Randomness<Self::Hash>;
 	type KittyIndex: Parameter + AtLeast32BitUnsigned + Bounded + Default + Copy + fmt::Display;
+	/// Number of blocks between breeding and the kitten's birth
+	type GestationPeriod: Get<Self::BlockNumber>;
+	/// Deposit reserved from the minter of each kitty, refunded on burn
+	type KittyDeposit: Get<BalanceOf<Self, I>>;
+	/// Hooks run on every change of kitty ownership, can veto the change
+	type OnTransfer: OnKittyTransfer<Self::AccountId, Self::KittyIndex>;
+	/// Identifier of assets, other than the native currency, kitties can be priced in
+	type AssetId: Parameter + Copy;
+	/// Settles purchases of kitties priced in assets
+	type Assets: FungibleAssets<Self::AccountId, AssetId = Self::AssetId, Balance = BalanceOf<Self, I>>;
+	type CollectionId: Parameter + AtLeast32BitUnsigned + Bounded + Default + Copy;
+	/// Maximum length of collection names
+	type StringLimit: Get<u32>;
+	/// Maximum length of attribute keys
+	type KeyLimit: Get<u32>;
+	/// Maximum length of attribute values
+	type ValueLimit: Get<u32>;
+	/// Maximum number of attributes per kitty, bounds the cost of burning a kitty
+	type MaxAttributes: Get<u32>;
+	/// Deposit reserved per byte of attribute key and value
+	type AttributeDepositPerByte: Get<BalanceOf<Self, I>>;
+	/// Maximum length of kitty names
+	type NameLimit: Get<u32>;
+	/// Deposit reserved for naming a kitty
+	type NameDeposit: Get<BalanceOf<Self, I>>;
+	/// Maximum length of metadata URIs
+	type UriLimit: Get<u32>;
+	/// Number of times DNA is regenerated when it clashes with an existing kitty's
+	type MaxDnaRetries: Get<u32>;
+	/// Origin allowed to create tournaments
+	type TournamentOrigin: EnsureOrigin<Self::Origin>;
+	/// Maximum number of entrants per tournament, bounds the matches fought per tournament and block
+	type MaxBracketSize: Get<u32>;
+	/// Maximum number of tournaments in registration or in progress
+	type MaxActiveTournaments: Get<u32>;
+	/// Tournament prize pools and vaulted kitties are held by sub accounts of this module id
+	type ModuleId: Get<ModuleId>;
+	/// Maximum number of items in batch calls
+	type MaxBatchSize: Get<u32>;
 }
 
-//type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
-type BalanceOf<T> = <T as pallet_balances::Config>::Balance;
+type BalanceOf<T, I = DefaultInstance> = <<T as Config<I>>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
 decl_storage! {
-	trait Store for Module<T: Config> as Kitties {
+	trait Store for Module<T: Config<I>, I: Instance = DefaultInstance> as Kitties {
 		/// Stores all the kitties, key is the kitty id
 		/// Implemented via https://substrate.dev/rustdocs/v3.0.0/frame_support/storage/trait.StorageDoubleMap.html
 		pub Kitties get(fn kitties): double_map hasher(blake2_128_concat) T::AccountId, hasher(blake2_128_concat) T::KittyIndex => Option<Kitty>;
+		/// Stores kitty owners, key is the kitty id
+		pub KittyOwner get(fn kitty_owner): map hasher(blake2_128_concat) T::KittyIndex => Option<T::AccountId>;
+		/// Stores the kitty id holding a DNA, key is the DNA
+		pub DnaKitties get(fn dna_kitty): map hasher(blake2_128_concat) [u8; 16] => Option<T::KittyIndex>;
+		/// Stores the number of live kitties carrying a trait, keys are the gene and its value
+		pub TraitCounts get(fn trait_count): double_map hasher(twox_64_concat) Gene, hasher(twox_64_concat) u8 => u32;
+		/// Stores the number of live kitties
+		pub KittyCount get(fn kitty_count): u32;
+		/// Stores open battle challenges, key is the challenger kitty id
+		pub Challenges get(fn challenges): map hasher(blake2_128_concat) T::KittyIndex => Option<Challenge<T::AccountId, T::KittyIndex, BalanceOf<T, I>>>;
+		/// Stores kitty experience and level, key is the kitty id
+		pub Progress get(fn progress): map hasher(blake2_128_concat) T::KittyIndex => KittyProgress;
+		/// Stores tournaments, key is the tournament id
+		pub Tournaments get(fn tournaments): map hasher(twox_64_concat) u32 => Option<Tournament<T::AccountId, BalanceOf<T, I>, T::BlockNumber, T::KittyIndex>>;
+		/// Stores tournament matches as \[kitty_a, kitty_b, winner\], keys are the tournament id and round
+		pub TournamentMatches get(fn tournament_matches): double_map hasher(twox_64_concat) u32, hasher(twox_64_concat) u32 => Vec<(T::KittyIndex, T::KittyIndex, T::KittyIndex)>;
+		/// Stores ids of tournaments in registration or in progress
+		pub ActiveTournaments get(fn active_tournaments): Vec<u32>;
+		pub NextTournamentId get(fn next_tournament_id): u32;
 		/// Stores parent ids, key is the child kitty id
 		/// Implemented via https://substrate.dev/rustdocs/v3.0.0/frame_support/storage/trait.StorageMap.html
 		pub Parents get(fn parents): map hasher(blake2_128_concat) T::KittyIndex => Option<(T::KittyIndex, T::KittyIndex)>;
-		pub Prices get(fn prices): map hasher(blake2_128_concat) T::KittyIndex => Option<BalanceOf<T>>;
+		/// Stores ids of the kitties burned to fuse a kitty, in fusing order, key is the fused kitty id
+		pub FusedFrom get(fn fused_from): map hasher(blake2_128_concat) T::KittyIndex => Option<(T::KittyIndex, T::KittyIndex)>;
+		/// Stores pregnancies, key is the momma kitty id
+		pub Pregnancies get(fn pregnancies): map hasher(blake2_128_concat) T::KittyIndex => Option<Pregnancy<T::AccountId, T::KittyIndex, T::BlockNumber>>;
+		/// Stores momma ids due to give birth, key is the due block
+		pub Births get(fn births): map hasher(twox_64_concat) T::BlockNumber => Vec<T::KittyIndex>;
+		/// Stores deposits reserved for kitties, key is the kitty id
+		pub Deposits get(fn deposits): map hasher(blake2_128_concat) T::KittyIndex => Option<(T::AccountId, BalanceOf<T, I>)>;
+		/// Stores the account approved to transfer the kitty, key is the kitty id
+		pub Approvals get(fn approvals): map hasher(blake2_128_concat) T::KittyIndex => Option<T::AccountId>;
+		/// Stores operators allowed to transfer all of the owner's kitties, keys are owner and operator
+		pub Operators get(fn operators): double_map hasher(blake2_128_concat) T::AccountId, hasher(blake2_128_concat) T::AccountId => bool;
+		/// Stores siring offers, key is the sire kitty id
+		pub SiringOffers get(fn siring_offers): map hasher(blake2_128_concat) T::KittyIndex => Option<SiringOffer<T::AccountId, BalanceOf<T, I>, T::BlockNumber>>;
+		pub Prices get(fn prices): map hasher(blake2_128_concat) T::KittyIndex => Option<BalanceOf<T, I>>;
+		/// Stores the asset a kitty is priced in, key is the kitty id. Priced in native currency if absent
+		pub PriceAssets get(fn price_assets): map hasher(blake2_128_concat) T::KittyIndex => Option<T::AssetId>;
+		/// Stores assets kitties can be priced in
+		pub AcceptedAssets get(fn accepted_assets) config(): Vec<T::AssetId>;
+		/// Stores kitty attributes, keys are the kitty id and attribute key
+		pub Attributes get(fn attributes): double_map hasher(blake2_128_concat) T::KittyIndex, hasher(blake2_128_concat) Vec<u8> => Option<Attribute<T::AccountId, BalanceOf<T, I>>>;
+		/// Stores kitty names, key is the kitty id
+		pub Names get(fn names): map hasher(blake2_128_concat) T::KittyIndex => Option<KittyName<T::AccountId, BalanceOf<T, I>>>;
+		/// Stores the kitty id holding a name, key is the name
+		pub NamedKitties get(fn named_kitty): map hasher(blake2_128_concat) Vec<u8> => Option<T::KittyIndex>;
+		/// Stores transfers awaiting acceptance, key is the kitty id
+		pub TransferOffers get(fn transfer_offers): map hasher(blake2_128_concat) T::KittyIndex => Option<TransferOffer<T::AccountId, T::BlockNumber>>;
+		/// Stores ids of kitties with transfer offers due to expire, key is the block the offer expires
+		pub TransferOfferExpiries get(fn transfer_offer_expiries): map hasher(twox_64_concat) T::BlockNumber => Vec<T::KittyIndex>;
+		/// Stores offers to lend kitties, key is the kitty id
+		pub LendOffers get(fn lend_offers): map hasher(blake2_128_concat) T::KittyIndex => Option<LendOffer<T::AccountId, BalanceOf<T, I>, T::BlockNumber>>;
+		/// Stores lent kitties, key is the kitty id
+		pub Rentals get(fn rentals): map hasher(blake2_128_concat) T::KittyIndex => Option<Rental<T::AccountId, T::BlockNumber>>;
+		/// Stores ids of kitties due to return to their owners, key is the block the rental expires
+		pub RentalExpiries get(fn rental_expiries): map hasher(twox_64_concat) T::BlockNumber => Vec<T::KittyIndex>;
+		/// Stores loans against kitties, key is the collateral kitty id
+		pub Loans get(fn loans): map hasher(blake2_128_concat) T::KittyIndex => Option<Loan<T::AccountId, BalanceOf<T, I>, T::BlockNumber>>;
+		/// Stores the number of shares issued for vaulted kitties, key is the kitty id
+		pub Vaults get(fn vaults): map hasher(blake2_128_concat) T::KittyIndex => Option<u64>;
+		/// Stores shares of vaulted kitties, keys are the kitty id and holder
+		pub Shares get(fn shares): double_map hasher(blake2_128_concat) T::KittyIndex, hasher(blake2_128_concat) T::AccountId => u64;
+		/// Stores kitty metadata, key is the kitty id
+		pub KittyMetadata get(fn kitty_metadata): map hasher(blake2_128_concat) T::KittyIndex => Option<Metadata<T::Hash>>;
+		/// Stores collection metadata, key is the collection id
+		pub CollectionMetadata get(fn collection_metadata): map hasher(blake2_128_concat) T::CollectionId => Option<Metadata<T::Hash>>;
+		/// Stores collections, key is the collection id
+		pub Collections get(fn collections): map hasher(blake2_128_concat) T::CollectionId => Option<Collection<T::AccountId>>;
+		/// Stores kitty ids of collections, keys are the collection id and kitty index within the collection
+		pub CollectionKitties get(fn collection_kitties): double_map hasher(blake2_128_concat) T::CollectionId, hasher(twox_64_concat) u32 => Option<T::KittyIndex>;
+		/// Stores whether kitties are soulbound, never to change hands, key is the kitty id
+		pub Soulbound get(fn soulbound): map hasher(blake2_128_concat) T::KittyIndex => bool;
+		/// Stores collection id and kitty index within the collection, key is the kitty id
+		pub KittyCollection get(fn kitty_collection): map hasher(blake2_128_concat) T::KittyIndex => Option<(T::CollectionId, u32)>;
+		pub NextCollectionId get(fn next_collection_id): T::CollectionId;
 		/// Stores the next kitty ID
 		// Implemented via https://substrate.dev/rustdocs/v3.0.0/frame_support/storage/trait.StorageValue.html
 		pub NextKittyId get(fn next_kitty_id): T::KittyIndex;
+		/// Storage layout version, new chains start out at the latest
+		pub StorageVersion get(fn storage_version) build(|_| Releases::V2): Releases;
+	}
+	add_extra_genesis {
+		/// Assets created at genesis, eg. local test assets, as \[asset_id, owner, min_balance\]
+		config(genesis_assets): Vec<(T::AssetId, T::AccountId, BalanceOf<T, I>)>;
+		build(|config: &GenesisConfig<T, I>| {
+			for (asset_id, owner, min_balance) in config.genesis_assets.iter() {
+				T::Assets::create(*asset_id, owner, *min_balance).expect("genesis assets are new; qed");
+			}
+		});
 	}
 }
 
 decl_event! {
-	pub enum Event<T> where
+	pub enum Event<T, I: Instance = DefaultInstance> where
 		<T as frame_system::Config>::AccountId,
-		Balance = BalanceOf<T>,
-		<T as Config>::KittyIndex,
+		<T as frame_system::Config>::BlockNumber,
+		<T as frame_system::Config>::Hash,
+		Balance = BalanceOf<T, I>,
+		<T as Config<I>>::KittyIndex,
+		<T as Config<I>>::AssetId,
+		<T as Config<I>>::CollectionId,
 	{
 		/// A kitty is created. \[owner, kitty_id, kitty\]
 		KittyCreated(AccountId, KittyIndex, Kitty),
 
-		/// A kitty is bred. \[owner, kitty_id, kitty_child, momma_kitty, papa_kitty\]
-		KittyBred(AccountId, KittyIndex, Kitty, Kitty, Kitty),
+		/// A kitty is bred, kitten is due at a later block. \[owner, child_id, momma_id, papa_id, due_block\]
+		KittyBred(AccountId, KittyIndex, KittyIndex, KittyIndex, BlockNumber),
+
+		/// A bred kitten is born. \[owner, kitty_id, kitty\]
+		KittyBorn(AccountId, KittyIndex, Kitty),
+
+		/// A kitten couldn't be given unique DNA and isn't born, deposit is refunded. \[owner, kitty_id, momma_id\]
+		KittyBirthFailed(AccountId, KittyIndex, KittyIndex),
 
 		/// A kitty is transfered. \[owner, new_owner, kitty_id, kitty\]
 		KittyTransfered(AccountId, AccountId, KittyIndex, Kitty),
 
+		/// A kitty transfer awaits the recipient's acceptance. \[owner, recipient, kitty_id, expires\]
+		TransferOffered(AccountId, AccountId, KittyIndex, BlockNumber),
+
+		/// A pending transfer is cancelled by the owner or declined by the recipient. \[owner, recipient, kitty_id\]
+		TransferOfferCancelled(AccountId, AccountId, KittyIndex),
+
+		/// A pending transfer expires unaccepted. \[owner, recipient, kitty_id\]
+		TransferOfferExpired(AccountId, AccountId, KittyIndex),
+
 		/// A kitty price is set. \[owner, kitty_id, price\]
 		KittyPriceSet(AccountId, KittyIndex, Option<Balance>),
 
+		/// A kitty price is set in an asset. \[owner, kitty_id, asset_id, price\]
+		KittyPriceSetInAsset(AccountId, KittyIndex, AssetId, Balance),
+
+		/// Assets kitties can be priced in are set. \[asset_ids\]
+		AcceptedAssetsSet(Vec<AssetId>),
+
 		/// A kitty is bought. \[owner, new_owner, kitty_id, price\]
 		KittyBought(AccountId, AccountId, KittyIndex, Balance),
+
+		/// A kitty is offered for siring. \[owner, kitty_id, fee, expires\]
+		SiringOffered(AccountId, KittyIndex, Balance, BlockNumber),
+
+		/// A siring offer is withdrawn. \[owner, kitty_id\]
+		SiringCancelled(AccountId, KittyIndex),
+
+		/// An offered sire is used for breeding. \[breeder, sire_owner, sire_id, fee\]
+		KittySired(AccountId, AccountId, KittyIndex, Balance),
+
+		/// A kitty is burned. \[owner, kitty_id\]
+		KittyBurned(AccountId, KittyIndex),
+
+		/// A kitty is offered for lending. \[owner, kitty_id, borrower, duration, fee\]
+		KittyLendOffered(AccountId, KittyIndex, AccountId, BlockNumber, Balance),
+
+		/// A lend offer is withdrawn. \[owner, kitty_id\]
+		KittyLendCancelled(AccountId, KittyIndex),
+
+		/// A kitty is borrowed. \[owner, borrower, kitty_id, fee, expires\]
+		KittyBorrowed(AccountId, AccountId, KittyIndex, Balance, BlockNumber),
+
+		/// A rental has expired, usage rights return to the owner. \[owner, borrower, kitty_id\]
+		KittyReturned(AccountId, AccountId, KittyIndex),
+
+		/// A loan against a kitty is requested. \[borrower, kitty_id, amount, interest, due\]
+		LoanRequested(AccountId, KittyIndex, Balance, Balance, BlockNumber),
+
+		/// A loan request is withdrawn. \[borrower, kitty_id\]
+		LoanRequestCancelled(AccountId, KittyIndex),
+
+		/// A loan is funded, the kitty is locked. \[lender, borrower, kitty_id, amount\]
+		LoanFunded(AccountId, AccountId, KittyIndex, Balance),
+
+		/// A loan is repaid with interest, the kitty is unlocked. \[borrower, lender, kitty_id, repaid\]
+		LoanRepaid(AccountId, AccountId, KittyIndex, Balance),
+
+		/// An overdue loan's kitty is claimed by the lender. \[lender, borrower, kitty_id\]
+		CollateralClaimed(AccountId, AccountId, KittyIndex),
+
+		/// A kitty is vaulted and shares issued to its owner. \[owner, kitty_id, shares\]
+		KittyVaulted(AccountId, KittyIndex, u64),
+
+		/// Shares of a vaulted kitty change hands. \[from, to, kitty_id, shares\]
+		SharesTransferred(AccountId, AccountId, KittyIndex, u64),
+
+		/// A vaulted kitty is redeemed for all of its shares. \[owner, kitty_id\]
+		KittyRedeemed(AccountId, KittyIndex),
+
+		/// An item of a batch call is done. \[index, result\]
+		BatchItemCompleted(u32, DispatchResult),
+
+		/// Two kitties are burned and fused into a new one. \[owner, kitty_a, kitty_b, kitty_id\]
+		KittiesFused(AccountId, KittyIndex, KittyIndex, KittyIndex),
+
+		/// A kitty transfer approval is set or revoked. \[owner, spender, kitty_id\]
+		KittyApproved(AccountId, Option<AccountId>, KittyIndex),
+
+		/// An operator is allowed or disallowed for all of owner's kitties. \[owner, operator, approved\]
+		OperatorApproved(AccountId, AccountId, bool),
+
+		/// A collection is created. \[owner, collection_id\]
+		CollectionCreated(AccountId, CollectionId),
+
+		/// Collection settings are changed. \[collection_id, royalty, transferable\]
+		CollectionConfigSet(CollectionId, Permill, bool),
+
+		/// A kitty is minted into a collection. \[collection_id, kitty_index, kitty_id\]
+		CollectionKittyMinted(CollectionId, u32, KittyIndex),
+
+		/// A royalty is paid on a sale. \[collection_id, collection_owner, royalty\]
+		RoyaltyPaid(CollectionId, AccountId, Balance),
+
+		/// A kitty attribute is set. \[kitty_id, key, value, deposit\]
+		AttributeSet(KittyIndex, Vec<u8>, Vec<u8>, Balance),
+
+		/// A kitty attribute is cleared. \[kitty_id, key\]
+		AttributeCleared(KittyIndex, Vec<u8>),
+
+		/// A kitty is named. \[owner, kitty_id, name\]
+		KittyNamed(AccountId, KittyIndex, Vec<u8>),
+
+		/// A kitty name is cleared, freeing it for others. \[owner, kitty_id, name\]
+		KittyNameCleared(AccountId, KittyIndex, Vec<u8>),
+
+		/// Kitty metadata is set. \[kitty_id, uri, hash\]
+		MetadataSet(KittyIndex, Vec<u8>, Hash),
+
+		/// Kitty metadata is frozen. \[kitty_id\]
+		MetadataFrozen(KittyIndex),
+
+		/// Collection metadata is set. \[collection_id, uri, hash\]
+		CollectionMetadataSet(CollectionId, Vec<u8>, Hash),
+
+		/// Collection metadata is frozen. \[collection_id\]
+		CollectionMetadataFrozen(CollectionId),
+
+		/// A kitty challenges another to a battle. \[owner, kitty_id, opponent_id, wager\]
+		BattleChallenged(AccountId, KittyIndex, KittyIndex, Balance),
+
+		/// A battle challenge is withdrawn. \[owner, kitty_id\]
+		BattleChallengeCancelled(AccountId, KittyIndex),
+
+		/// A battle is fought, strikes are in order for replay. \[challenger_id, opponent_id, winner_id, strikes\]
+		BattleFought(KittyIndex, KittyIndex, KittyIndex, Vec<Strike>),
+
+		/// The winner's owner takes both wagers. \[winner_owner, loser_owner, wager\]
+		WagerWon(AccountId, AccountId, Balance),
+
+		/// A tournament is created. \[tournament_id, entry_fee, deadline, bracket_size\]
+		TournamentCreated(u32, Balance, BlockNumber, u32),
+
+		/// A kitty enters a tournament. \[owner, tournament_id, kitty_id\]
+		TournamentEntered(AccountId, u32, KittyIndex),
+
+		/// Registration is closed and the first round fought. \[tournament_id, entrants\]
+		TournamentStarted(u32, u32),
+
+		/// A tournament match is fought, strikes are in order for replay. \[tournament_id, round, kitty_a, kitty_b, winner, strikes\]
+		TournamentMatch(u32, u32, KittyIndex, KittyIndex, KittyIndex, Vec<Strike>),
+
+		/// A tournament is won, the prize pool is paid out. \[tournament_id, kitty_id, owner, prize\]
+		TournamentWon(u32, KittyIndex, AccountId, Balance),
+
+		/// A kitty levels up and its DNA evolves. \[owner, kitty_id, level, kitty\]
+		KittyEvolved(AccountId, KittyIndex, u8, Kitty),
+
+		/// A tournament is cancelled for lack of entrants, entry fees are refunded. \[tournament_id\]
+		TournamentCancelled(u32),
 	}
 }
 
 decl_error! {
-	pub enum Error for Module<T: Config> {
+	pub enum Error for Module<T: Config<I>, I: Instance> {
 		KittiesIdOverflow,
 		KittyNotOwned, // Note: decl_error! doesn't allow for parametrized Enum values, ie. will get error if use a param er (u32): ^ no rules expected this token in macro call
 		KittiesBredFromSameGenderCouple,
 		KittyNotForSale,
 		KittyPriceTooLow,
+		KittyAlreadyPregnant,
+		KittyNotMale,
+		SiringOfferNotFound,
+		SiringOfferExpired,
+		KittyNotApproved,
+		AssetNotAccepted,
+		CollectionIdOverflow,
+		CollectionNotFound,
+		CollectionNameTooLong,
+		CollectionSupplyExhausted,
+		CollectionNotTransferable,
+		CollectionKittyNotFound,
+		NotCollectionOwner,
+		AttributeKeyTooLong,
+		AttributeValueTooLong,
+		AttributeNotFound,
+		TooManyAttributes,
+		NameEmpty,
+		NameTooLong,
+		NameTaken,
+		NameNotFound,
+		UriTooLong,
+		MetadataNotFound,
+		MetadataIsFrozen,
+		DuplicateDna,
+		ChallengeExists,
+		ChallengeNotFound,
+		CannotFightSelf,
+		CannotFuseSelf,
+		KittyLent,
+		LendOfferNotFound,
+		CannotLendToSelf,
+		KittyCollateralised,
+		LoanExists,
+		LoanNotFound,
+		LoanAlreadyFunded,
+		LoanOverdue,
+		LoanNotOverdue,
+		KittyVaulted,
+		KittySoulbound,
+		TransferOfferNotFound,
+		TransferOfferExpired,
+		CannotTransferToSelf,
+		BatchTooLarge,
+		VaultNotFound,
+		InvalidShares,
+		InsufficientShares,
+		InvalidBracketSize,
+		InvalidDeadline,
+		EntryFeeTooLow,
+		TooManyTournaments,
+		TournamentNotFound,
+		TournamentRegistrationClosed,
+		TournamentFull,
+		AlreadyEntered,
 	}
 }
 
 decl_module! {
-	pub struct Module<T: Config> for enum Call where origin: T::Origin {
-		type Error = Error<T>;
+	pub struct Module<T: Config<I>, I: Instance = DefaultInstance> for enum Call where origin: T::Origin {
+		type Error = Error<T, I>;
 
 		fn deposit_event() = default;
 
+		const GestationPeriod: T::BlockNumber = T::GestationPeriod::get();
+		const KittyDeposit: BalanceOf<T, I> = T::KittyDeposit::get();
+		const StringLimit: u32 = T::StringLimit::get();
+		const KeyLimit: u32 = T::KeyLimit::get();
+		const ValueLimit: u32 = T::ValueLimit::get();
+		const MaxAttributes: u32 = T::MaxAttributes::get();
+		const AttributeDepositPerByte: BalanceOf<T, I> = T::AttributeDepositPerByte::get();
+		const NameLimit: u32 = T::NameLimit::get();
+		const NameDeposit: BalanceOf<T, I> = T::NameDeposit::get();
+		const UriLimit: u32 = T::UriLimit::get();
+		const MaxDnaRetries: u32 = T::MaxDnaRetries::get();
+		const MaxBatchSize: u32 = T::MaxBatchSize::get();
+		const MaxBracketSize: u32 = T::MaxBracketSize::get();
+		const MaxActiveTournaments: u32 = T::MaxActiveTournaments::get();
+
+		/// Give birth to all kittens due at this block, end rentals and transfer offers due and advance
+		/// active tournaments
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let due = Births::<T, I>::take(now);
+			for momma_id in due.iter() {
+				Self::give_birth(*momma_id);
+			}
+			let returns = RentalExpiries::<T, I>::take(now);
+			for kitty_id in returns.iter() {
+				if let Some(rental) = Rentals::<T, I>::take(kitty_id) {
+					// the borrower's challenge goes with the kitty
+					if Self::challenges(kitty_id).map_or(false, |c| c.owner == rental.borrower) {
+						Self::drop_challenge(*kitty_id);
+					}
+					Self::deposit_event(RawEvent::KittyReturned(rental.owner, rental.borrower, *kitty_id));
+				}
+			}
+			let expired = TransferOfferExpiries::<T, I>::take(now);
+			for kitty_id in expired.iter() {
+				if let Some(offer) = TransferOffers::<T, I>::take(kitty_id) {
+					Self::deposit_event(RawEvent::TransferOfferExpired(offer.owner, offer.recipient, *kitty_id));
+				}
+			}
+			let (due, returns, expired) = (due.len() as Weight, returns.len() as Weight, expired.len() as Weight);
+			let weight = T::DbWeight::get().reads_writes(3 + 2 * due + 2 * returns + expired, 3 + 4 * due + 2 * returns + expired);
+			weight.saturating_add(Self::advance_tournaments(now))
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			let mut weight = T::DbWeight::get().reads(1);
+			if Self::storage_version() < Releases::V2 {
+				weight = weight.saturating_add(Self::migrate_to_v2());
+			}
+			weight
+		}
+
 		/// Create a new kitty
 		#[weight = 1000]
+		#[transactional]
 		pub fn create(origin) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
 
-			// ensure no id overflow
-			let kitty_id = Self::get_next_kitty_id()?;
-
 			// FIXME: discover how using_encoded() works on such tuple...
 			// Generate a random 128bit value
-			let dna = Self::random_value(&sender);
-
-			// Create and store kitty
-			let kitty = Kitty(dna);
-			// note, setter isn't created as part of doublemap decl_storage!
-			Kitties::<T>::insert(&sender, kitty_id, kitty.clone());
+			let random = Self::random_value(&sender);
+			let dna = Self::unique_dna(|attempt| reroll(random, attempt)).ok_or(Error::<T, I>::DuplicateDna)?;
 
-			// Emit event
-			Self::deposit_event(RawEvent::KittyCreated(sender, kitty_id, kitty));
+			let kitty_id = Self::mint(&sender, &sender, dna)?;
 
 			frame_support::debug::RuntimeLogger::init();
 			frame_support::debug::info!("##### create(): dna: {:?}, next_kitty_id: {}", dna, kitty_id);
@@ -128,35 +695,59 @@ decl_module! {
         /// a. A kitty have gender, based on DNA
         /// b. Kitty owner can choose two kitties with opposite gender to breed a new kitten
         /// c. New kitten should inherits the DNA from parents
+        /// d. Kitten is born `GestationPeriod` blocks later, see `on_initialize()`
 		#[weight = 1000]
+		#[transactional]
 		pub fn breed(origin, parent1_id: T::KittyIndex, parent2_id: T::KittyIndex) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
-			let parent1 = Self::kitties(&sender, parent1_id).ok_or(Error::<T>::KittyNotOwned)?;
-			let parent2 = Self::kitties(&sender, parent2_id).ok_or(Error::<T>::KittyNotOwned)?;
-			let (momma, pappa) = get_female_male(&parent1, &parent2).ok_or(Error::<T>::KittiesBredFromSameGenderCouple)?;
-
-			let child_id = Self::get_next_kitty_id()?;
+			Self::do_breed(&sender, parent1_id, parent2_id)
+		}
 
-			// obtain child dna from parents' dnas
-			let mixer: [u8; 16] = Self::random_value(&sender);
-			let child_dna: [u8; 16] = mix_dna(mixer, momma.0, pappa.0);
-			// ensure recording tuple order: momma, pappa
-			let (momma_id, poppa_id) = if parent1.get_gender() == Gender::Female {
-				(parent1_id, parent2_id)
-			} else {
-				(parent2_id, parent1_id)
-			};
+		/// Offer a male kitty for siring by other owners' kitties, for a stud fee, for `duration` blocks
+		#[weight = 1000]
+		pub fn offer_siring(origin, kitty_id: T::KittyIndex, fee: BalanceOf<T, I>, duration: T::BlockNumber) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let kitty = Self::kitties(&sender, kitty_id).ok_or(Error::<T, I>::KittyNotOwned)?;
+			ensure!(!Rentals::<T, I>::contains_key(kitty_id), Error::<T, I>::KittyLent);
+			ensure!(kitty.get_gender() == Gender::Male, Error::<T, I>::KittyNotMale);
+			let expires = <frame_system::Module<T>>::block_number().saturating_add(duration);
+			SiringOffers::<T, I>::insert(kitty_id, SiringOffer { owner: sender.clone(), fee, expires });
+			Self::deposit_event(RawEvent::SiringOffered(sender, kitty_id, fee, expires));
+			Ok(())
+		}
 
-			let child = Kitty(child_dna);
-			Kitties::<T>::insert(&sender, child_id, child.clone());
-			Parents::<T>::insert(child_id, (momma_id, poppa_id));
+		#[weight = 1000]
+		pub fn cancel_siring(origin, kitty_id: T::KittyIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let offer = Self::siring_offers(kitty_id).ok_or(Error::<T, I>::SiringOfferNotFound)?;
+			ensure!(offer.owner == sender, Error::<T, I>::KittyNotOwned);
+			SiringOffers::<T, I>::remove(kitty_id);
+			Self::deposit_event(RawEvent::SiringCancelled(sender, kitty_id));
+			Ok(())
+		}
 
-			frame_support::debug::RuntimeLogger::init();
-			frame_support::debug::info!("##### breed(): child dna: {:?}, momma_id: {}, poppa_id: {}", child_dna, momma_id, poppa_id);
+		/// Breed own female kitty with a sire offered by another owner, paying the stud fee to the sire's owner.
+		/// Sire isn't transferred, the kitten goes to the momma's owner
+		#[weight = 1000]
+		#[transactional]
+		pub fn breed_with_sire(origin, momma_id: T::KittyIndex, sire_id: T::KittyIndex, max_fee: BalanceOf<T, I>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let momma = Self::usable_kitty(&sender, momma_id)?;
+			let offer = Self::siring_offers(sire_id).ok_or(Error::<T, I>::SiringOfferNotFound)?;
+			ensure!(offer.expires > <frame_system::Module<T>>::block_number(), Error::<T, I>::SiringOfferExpired);
+			// offer goes stale if the sire has changed hands since
+			let sire = Self::kitties(&offer.owner, sire_id).ok_or(Error::<T, I>::SiringOfferNotFound)?;
+			// or has been lent out since
+			ensure!(!Rentals::<T, I>::contains_key(sire_id), Error::<T, I>::KittyLent);
+			ensure!(momma.get_gender() == Gender::Female, Error::<T, I>::KittiesBredFromSameGenderCouple);
+			ensure!(offer.fee <= max_fee, Error::<T, I>::KittyPriceTooLow);
 
-			Self::deposit_event(RawEvent::KittyBred(sender, child_id, child, (*momma).clone(), (*pappa).clone()));
+			if sender != offer.owner {
+				T::Currency::transfer(&sender, &offer.owner, offer.fee, ExistenceRequirement::KeepAlive)?;
+			}
+			Self::deposit_event(RawEvent::KittySired(sender.clone(), offer.owner, sire_id, offer.fee));
 
-			Ok(())
+			Self::conceive(&sender, momma_id, &momma, sire_id, &sire)
 		}
 
 		/// Design transfer feature
@@ -164,76 +755,1234 @@ decl_module! {
 		#[weight = 1000]
 		pub fn transfer(origin, new_owner: T::AccountId, kitty_id: T::KittyIndex) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
-			Kitties::<T>::try_mutate_exists(sender.clone(), kitty_id, |kitty| -> DispatchResult {
-				if sender == new_owner && kitty.is_some() {
-					Ok(())					
-				} else {
-					match kitty.take() {  // not sure why, but take() is required to remove from storage
-						None    => Err(Error::<T>::KittyNotOwned.into()),
-						Some(k) => {
-							Kitties::<T>::insert(&new_owner, kitty_id, k.clone());
-							// Prices::<T>::insert(kitty_id, None);
-							Self::deposit_event(RawEvent::KittyTransfered(sender, new_owner, kitty_id, k));
-							Ok(())
-						}
-					}
-				}
-			})
+			Self::do_transfer(&sender, &new_owner, kitty_id)
 		}
 
+		/// Offer the kitty to `recipient`, who has `duration` blocks to accept it. Unaccepted offers are
+		/// pruned once expired
 		#[weight = 1000]
-		pub fn set_price(origin, kitty_id: T::KittyIndex, new_price: Option<BalanceOf<T>>) -> DispatchResult {
-			// bryan's impl
+		pub fn offer_transfer(origin, recipient: T::AccountId, kitty_id: T::KittyIndex, duration: T::BlockNumber) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
-			ensure!(Kitties::<T>::contains_key(&sender, kitty_id), Error::<T>::KittyNotOwned);  // more performant than fetch cause no serialization
-			Prices::<T>::mutate_exists(kitty_id, |price| -> () {
-				*price = new_price;
-			});  // if returning a None, force erasue
-			Self::deposit_event(RawEvent::KittyPriceSet(sender, kitty_id, new_price));
+			ensure!(Kitties::<T, I>::contains_key(&sender, kitty_id), Error::<T, I>::KittyNotOwned);
+			ensure!(sender != recipient, Error::<T, I>::CannotTransferToSelf);
+			Self::ensure_transferable(kitty_id)?;
+			let expires = <frame_system::Module<T>>::block_number().saturating_add(duration.max(One::one()));
+			Self::remove_transfer_offer(kitty_id);
+			TransferOffers::<T, I>::insert(kitty_id, TransferOffer { owner: sender.clone(), recipient: recipient.clone(), expires });
+			TransferOfferExpiries::<T, I>::append(expires, kitty_id);
+			Self::deposit_event(RawEvent::TransferOffered(sender, recipient, kitty_id, expires));
 			Ok(())
+		}
+
+		/// Accept a kitty offered to sender
+		#[weight = 1000]
+		pub fn accept_transfer(origin, kitty_id: T::KittyIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let offer = Self::transfer_offers(kitty_id)
+				.filter(|offer| offer.recipient == sender)
+				.ok_or(Error::<T, I>::TransferOfferNotFound)?;
+			ensure!(offer.expires > <frame_system::Module<T>>::block_number(), Error::<T, I>::TransferOfferExpired);
+			Self::do_transfer(&offer.owner, &sender, kitty_id)
+		}
+
+		/// Withdraw a transfer offer as its owner, or decline it as its recipient
+		#[weight = 1000]
+		pub fn cancel_transfer(origin, kitty_id: T::KittyIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let offer = Self::transfer_offers(kitty_id)
+				.filter(|offer| offer.owner == sender || offer.recipient == sender)
+				.ok_or(Error::<T, I>::TransferOfferNotFound)?;
+			Self::remove_transfer_offer(kitty_id);
+			Self::deposit_event(RawEvent::TransferOfferCancelled(offer.owner, offer.recipient, kitty_id));
+			Ok(())
+		}
+
+		/// Approve `spender` to transfer the kitty on owner's behalf, or revoke approval with `None`
+		#[weight = 1000]
+		pub fn approve(origin, kitty_id: T::KittyIndex, spender: Option<T::AccountId>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(Kitties::<T, I>::contains_key(&sender, kitty_id), Error::<T, I>::KittyNotOwned);
+			if spender.is_some() {
+				Self::ensure_transferable(kitty_id)?;
+			}
+			Approvals::<T, I>::mutate_exists(kitty_id, |approved| -> () {
+				*approved = spender.clone();
+			});
+			Self::deposit_event(RawEvent::KittyApproved(sender, spender, kitty_id));
+			Ok(())
+		}
+
+		/// Allow or disallow `operator` to transfer all of owner's kitties
+		#[weight = 1000]
+		pub fn set_approval_for_all(origin, operator: T::AccountId, approved: bool) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			if approved {
+				Operators::<T, I>::insert(&sender, &operator, true);
+			} else {
+				Operators::<T, I>::remove(&sender, &operator);
+			}
+			Self::deposit_event(RawEvent::OperatorApproved(sender, operator, approved));
+			Ok(())
+		}
+
+		/// Transfer a kitty on behalf of its owner, requires an approval or being owner's operator
+		#[weight = 1000]
+		pub fn transfer_from(origin, owner: T::AccountId, new_owner: T::AccountId, kitty_id: T::KittyIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(
+				sender == owner
+					|| Self::approvals(kitty_id).as_ref() == Some(&sender)
+					|| Self::operators(&owner, &sender),
+				Error::<T, I>::KittyNotApproved
+			);
+			Self::do_transfer(&owner, &new_owner, kitty_id)
+		}
+
+		#[weight = 1000]
+		pub fn set_price(origin, kitty_id: T::KittyIndex, new_price: Option<BalanceOf<T, I>>) -> DispatchResult {
+			// bryan's impl, see do_set_price()
+			let sender = ensure_signed(origin)?;
+			Self::do_set_price(&sender, kitty_id, new_price)
 
 			// my impl - equivalent to above
 			// let sender = ensure_signed(origin)?;
-			// Self::kitties(&sender, kitty_id).ok_or(Error::<T>::KittyNotOwned)?;
+			// Self::kitties(&sender, kitty_id).ok_or(Error::<T, I>::KittyNotOwned)?;
 			// match new_price {
-			// 	Some(p) => { Prices::<T>::insert(kitty_id, p); }
+			// 	Some(p) => { Prices::<T, I>::insert(kitty_id, p); }
 			// 	None => ()
 			// }
 			// Self::deposit_event(RawEvent::KittyPriceSet(sender, kitty_id, new_price));
 			// Ok(())
 		}
 
+		/// Price the kitty in one of the accepted assets rather than native currency
 		#[weight = 1000]
-		pub fn buy(origin, new_owner: T::AccountId, kitty_id: T::KittyIndex, max_bid: BalanceOf<T>) -> DispatchResult {
+		pub fn set_price_in_asset(origin, kitty_id: T::KittyIndex, asset_id: T::AssetId, new_price: BalanceOf<T, I>) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
+			ensure!(Kitties::<T, I>::contains_key(&sender, kitty_id), Error::<T, I>::KittyNotOwned);
+			Self::ensure_transferable(kitty_id)?;
+			ensure!(Self::accepted_assets().contains(&asset_id), Error::<T, I>::AssetNotAccepted);
+			Prices::<T, I>::insert(kitty_id, new_price);
+			PriceAssets::<T, I>::insert(kitty_id, asset_id);
+			Self::deposit_event(RawEvent::KittyPriceSetInAsset(sender, kitty_id, asset_id, new_price));
+			Ok(())
+		}
+
+		/// Set assets kitties can be priced in, existing listings are unaffected
+		#[weight = 1000]
+		pub fn set_accepted_assets(origin, asset_ids: Vec<T::AssetId>) -> DispatchResult {
+			ensure_root(origin)?;
+			AcceptedAssets::<T, I>::put(&asset_ids);
+			Self::deposit_event(RawEvent::AcceptedAssetsSet(asset_ids));
+			Ok(())
+		}
+
+		/// Buy a listed kitty from its owner, paying up to `max_bid`
+		#[weight = T::Assets::transfer_weight().saturating_mul(2).saturating_add(1000)]
+		#[transactional]
+		pub fn buy(origin, kitty_id: T::KittyIndex, max_bid: BalanceOf<T, I>) -> DispatchResult {
+			let new_owner = ensure_signed(origin)?;
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T, I>::KittyNotOwned)?;
+			ensure!(owner != new_owner, Error::<T, I>::CannotTransferToSelf);
 
 			// transfer if prices are below max bid
-			Kitties::<T>::try_mutate_exists(sender.clone(), kitty_id, |kitty| -> DispatchResult {
-				let kitty = kitty.take().ok_or(Error::<T>::KittyNotOwned)?;   // will remove from map!
-				Prices::<T>::try_mutate_exists(kitty_id, |price| -> DispatchResult {
-					let price = price.take().ok_or(Error::<T>::KittyNotForSale)?;  // will remove from map!
-					ensure!(price <= max_bid, Error::<T>::KittyPriceTooLow);
-					<pallet_balances::Module<T> as Currency<T::AccountId>>::transfer(&new_owner, &sender, price, ExistenceRequirement::KeepAlive)?;  // KeepAlive = ensure enough funds in account to keep account alive
-					Kitties::<T>::insert(&new_owner, kitty_id, kitty);
-					Self::deposit_event(RawEvent::KittyBought(sender, new_owner, kitty_id, price));
+			Kitties::<T, I>::try_mutate_exists(owner.clone(), kitty_id, |kitty| -> DispatchResult {
+				let kitty = kitty.take().ok_or(Error::<T, I>::KittyNotOwned)?;   // will remove from map!
+				Prices::<T, I>::try_mutate_exists(kitty_id, |price| -> DispatchResult {
+					let price = price.take().ok_or(Error::<T, I>::KittyNotForSale)?;  // will remove from map!
+					ensure!(price <= max_bid, Error::<T, I>::KittyPriceTooLow);
+					Self::ensure_transferable(kitty_id)?;
+					ensure!(!Rentals::<T, I>::contains_key(kitty_id), Error::<T, I>::KittyLent);
+					Self::ensure_not_collateral(kitty_id)?;
+					T::OnTransfer::can_transfer(Some(&owner), Some(&new_owner), &kitty_id)?;
+					let asset = Self::price_assets(kitty_id);
+					// collection owner takes the royalty cut
+					let mut proceeds = price;
+					if let Some((collection_id, _)) = Self::kitty_collection(kitty_id) {
+						if let Some(collection) = Self::collections(collection_id) {
+							let royalty = collection.royalty * price;
+							if !royalty.is_zero() && collection.owner != owner {
+								Self::pay(asset, &new_owner, &collection.owner, royalty)?;
+								proceeds = price.saturating_sub(royalty);
+								Self::deposit_event(RawEvent::RoyaltyPaid(collection_id, collection.owner, royalty));
+							}
+						}
+					}
+					Self::pay(asset, &new_owner, &owner, proceeds)?;
+					PriceAssets::<T, I>::remove(kitty_id);
+					Kitties::<T, I>::insert(&new_owner, kitty_id, kitty);
+					KittyOwner::<T, I>::insert(kitty_id, &new_owner);
+					Approvals::<T, I>::remove(kitty_id);
+					Self::remove_transfer_offer(kitty_id);
+					// only unfunded loan requests are left, the new owner didn't make them
+					Loans::<T, I>::remove(kitty_id);
+					Self::drop_challenge(kitty_id);
+					T::OnTransfer::on_transfer(Some(&owner), Some(&new_owner), &kitty_id);
+					Self::deposit_event(RawEvent::KittyBought(owner, new_owner, kitty_id, price));
 					Ok(())
 				})
 			})
 		}
 
+		/// Create a collection, kitties are minted into it by the collection owner
+		#[weight = 1000]
+		pub fn create_collection(origin, name: Vec<u8>, max_supply: Option<u32>, royalty: Permill, transferable: bool) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(name.len() <= T::StringLimit::get() as usize, Error::<T, I>::CollectionNameTooLong);
+			let collection_id = NextCollectionId::<T, I>::try_mutate(|next_id| -> sp_std::result::Result<T::CollectionId, DispatchError> {
+				let current_id = *next_id;
+				*next_id = next_id.checked_add(&One::one()).ok_or(Error::<T, I>::CollectionIdOverflow)?;
+				Ok(current_id)
+			})?;
+			Collections::<T, I>::insert(collection_id, Collection {
+				owner: sender.clone(),
+				name,
+				max_supply,
+				minted: 0,
+				royalty,
+				transferable,
+			});
+			Self::deposit_event(RawEvent::CollectionCreated(sender, collection_id));
+			Ok(())
+		}
+
+		#[weight = 1000]
+		pub fn set_collection_config(origin, collection_id: T::CollectionId, royalty: Permill, transferable: bool) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Collections::<T, I>::try_mutate(collection_id, |collection| -> DispatchResult {
+				let collection = collection.as_mut().ok_or(Error::<T, I>::CollectionNotFound)?;
+				ensure!(collection.owner == sender, Error::<T, I>::NotCollectionOwner);
+				collection.royalty = royalty;
+				collection.transferable = transferable;
+				Ok(())
+			})?;
+			Self::deposit_event(RawEvent::CollectionConfigSet(collection_id, royalty, transferable));
+			Ok(())
+		}
+
+		/// Mint a new kitty into own collection, for `recipient`. Deposit is reserved from the collection owner.
+		/// Soulbound kitties, eg. event badges, stay with the recipient until burned
+		#[weight = 1000]
+		#[transactional]
+		pub fn mint_into(origin, collection_id: T::CollectionId, recipient: T::AccountId, soulbound: bool) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let mut collection = Self::collections(collection_id).ok_or(Error::<T, I>::CollectionNotFound)?;
+			ensure!(collection.owner == sender, Error::<T, I>::NotCollectionOwner);
+			ensure!(collection.max_supply.map_or(true, |max| collection.minted < max), Error::<T, I>::CollectionSupplyExhausted);
+
+			let random = Self::random_value(&recipient);
+			let dna = Self::unique_dna(|attempt| reroll(random, attempt)).ok_or(Error::<T, I>::DuplicateDna)?;
+			let kitty_id = Self::mint(&sender, &recipient, dna)?;
+
+			let kitty_index = collection.minted;
+			collection.minted = collection.minted.saturating_add(1);
+			Collections::<T, I>::insert(collection_id, collection);
+			CollectionKitties::<T, I>::insert(collection_id, kitty_index, kitty_id);
+			KittyCollection::<T, I>::insert(kitty_id, (collection_id, kitty_index));
+			if soulbound {
+				Soulbound::<T, I>::insert(kitty_id, true);
+			}
+			Self::deposit_event(RawEvent::CollectionKittyMinted(collection_id, kitty_index, kitty_id));
+			Ok(())
+		}
+
+		/// Transfer a collection's kitty, addressed by its index within the collection
+		#[weight = 1000]
+		pub fn transfer_in_collection(origin, new_owner: T::AccountId, collection_id: T::CollectionId, kitty_index: u32) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let kitty_id = Self::collection_kitty_id(collection_id, kitty_index)?;
+			Self::do_transfer(&sender, &new_owner, kitty_id)
+		}
+
+		/// Set the price of a collection's kitty, addressed by its index within the collection
+		#[weight = 1000]
+		pub fn set_price_in_collection(origin, collection_id: T::CollectionId, kitty_index: u32, new_price: Option<BalanceOf<T, I>>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let kitty_id = Self::collection_kitty_id(collection_id, kitty_index)?;
+			Self::do_set_price(&sender, kitty_id, new_price)
+		}
+
+		/// Burn a collection's kitty, addressed by its index within the collection
+		#[weight = 1000]
+		pub fn burn_in_collection(origin, collection_id: T::CollectionId, kitty_index: u32) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let kitty_id = Self::collection_kitty_id(collection_id, kitty_index)?;
+			Self::do_burn(&sender, kitty_id)
+		}
+
+		/// Set a kitty attribute, reserving a deposit per byte of key and value from the owner
+		#[weight = 1000]
+		#[transactional]
+		pub fn set_attribute(origin, kitty_id: T::KittyIndex, key: Vec<u8>, value: Vec<u8>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(Kitties::<T, I>::contains_key(&sender, kitty_id), Error::<T, I>::KittyNotOwned);
+			ensure!(key.len() <= T::KeyLimit::get() as usize, Error::<T, I>::AttributeKeyTooLong);
+			ensure!(value.len() <= T::ValueLimit::get() as usize, Error::<T, I>::AttributeValueTooLong);
+
+			// refund the previous deposit, then reserve for the new value
+			if let Some(old) = Self::attributes(kitty_id, &key) {
+				T::Currency::unreserve(&old.depositor, old.deposit);
+			} else {
+				let count = Attributes::<T, I>::iter_prefix(kitty_id).count();
+				ensure!(count < T::MaxAttributes::get() as usize, Error::<T, I>::TooManyAttributes);
+			}
+			let bytes: BalanceOf<T, I> = ((key.len() + value.len()) as u32).into();
+			let deposit = T::AttributeDepositPerByte::get().saturating_mul(bytes);
+			T::Currency::reserve(&sender, deposit)?;
+
+			Attributes::<T, I>::insert(kitty_id, &key, Attribute { value: value.clone(), depositor: sender, deposit });
+			Self::deposit_event(RawEvent::AttributeSet(kitty_id, key, value, deposit));
+			Ok(())
+		}
+
+		/// Clear a kitty attribute, refunding its deposit
+		#[weight = 1000]
+		pub fn clear_attribute(origin, kitty_id: T::KittyIndex, key: Vec<u8>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(Kitties::<T, I>::contains_key(&sender, kitty_id), Error::<T, I>::KittyNotOwned);
+			let attribute = Attributes::<T, I>::take(kitty_id, &key).ok_or(Error::<T, I>::AttributeNotFound)?;
+			T::Currency::unreserve(&attribute.depositor, attribute.deposit);
+			Self::deposit_event(RawEvent::AttributeCleared(kitty_id, key));
+			Ok(())
+		}
+
+		/// Name a kitty, names are unique across the registry. Renaming frees the previous name
+		#[weight = 1000]
+		#[transactional]
+		pub fn set_name(origin, kitty_id: T::KittyIndex, name: Vec<u8>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(Kitties::<T, I>::contains_key(&sender, kitty_id), Error::<T, I>::KittyNotOwned);
+			ensure!(!name.is_empty(), Error::<T, I>::NameEmpty);
+			ensure!(name.len() <= T::NameLimit::get() as usize, Error::<T, I>::NameTooLong);
+			ensure!(
+				Self::named_kitty(&name).map_or(true, |id| id == kitty_id),
+				Error::<T, I>::NameTaken
+			);
+
+			// refund the previous deposit, then reserve for the new name
+			if let Some(old) = Names::<T, I>::take(kitty_id) {
+				NamedKitties::<T, I>::remove(&old.name);
+				T::Currency::unreserve(&old.depositor, old.deposit);
+			}
+			let deposit = T::NameDeposit::get();
+			T::Currency::reserve(&sender, deposit)?;
+
+			NamedKitties::<T, I>::insert(&name, kitty_id);
+			Names::<T, I>::insert(kitty_id, KittyName { name: name.clone(), depositor: sender.clone(), deposit });
+			Self::deposit_event(RawEvent::KittyNamed(sender, kitty_id, name));
+			Ok(())
+		}
+
+		/// Clear a kitty name, refunding its deposit
+		#[weight = 1000]
+		pub fn clear_name(origin, kitty_id: T::KittyIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(Kitties::<T, I>::contains_key(&sender, kitty_id), Error::<T, I>::KittyNotOwned);
+			let name = Self::release_name(kitty_id).ok_or(Error::<T, I>::NameNotFound)?;
+			Self::deposit_event(RawEvent::KittyNameCleared(sender, kitty_id, name));
+			Ok(())
+		}
+
+		/// Set kitty's metadata URI and content hash, unless frozen
+		#[weight = 1000]
+		pub fn set_metadata(origin, kitty_id: T::KittyIndex, uri: Vec<u8>, hash: T::Hash) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(Kitties::<T, I>::contains_key(&sender, kitty_id), Error::<T, I>::KittyNotOwned);
+			KittyMetadata::<T, I>::try_mutate(kitty_id, |metadata| Self::update_metadata(metadata, uri.clone(), hash))?;
+			Self::deposit_event(RawEvent::MetadataSet(kitty_id, uri, hash));
+			Ok(())
+		}
+
+		/// Freeze kitty's metadata for good
+		#[weight = 1000]
+		pub fn freeze_metadata(origin, kitty_id: T::KittyIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(Kitties::<T, I>::contains_key(&sender, kitty_id), Error::<T, I>::KittyNotOwned);
+			KittyMetadata::<T, I>::try_mutate(kitty_id, Self::freeze)?;
+			Self::deposit_event(RawEvent::MetadataFrozen(kitty_id));
+			Ok(())
+		}
+
+		/// Set metadata URI and content hash shared by collection's kitties, unless frozen
+		#[weight = 1000]
+		pub fn set_collection_metadata(origin, collection_id: T::CollectionId, uri: Vec<u8>, hash: T::Hash) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let collection = Self::collections(collection_id).ok_or(Error::<T, I>::CollectionNotFound)?;
+			ensure!(collection.owner == sender, Error::<T, I>::NotCollectionOwner);
+			CollectionMetadata::<T, I>::try_mutate(collection_id, |metadata| Self::update_metadata(metadata, uri.clone(), hash))?;
+			Self::deposit_event(RawEvent::CollectionMetadataSet(collection_id, uri, hash));
+			Ok(())
+		}
+
+		/// Freeze collection's metadata for good
+		#[weight = 1000]
+		pub fn freeze_collection_metadata(origin, collection_id: T::CollectionId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let collection = Self::collections(collection_id).ok_or(Error::<T, I>::CollectionNotFound)?;
+			ensure!(collection.owner == sender, Error::<T, I>::NotCollectionOwner);
+			CollectionMetadata::<T, I>::try_mutate(collection_id, Self::freeze)?;
+			Self::deposit_event(RawEvent::CollectionMetadataFrozen(collection_id));
+			Ok(())
+		}
+
+		/// Challenge another kitty to a battle, reserving an optional wager
+		#[weight = 1000]
+		pub fn challenge(origin, kitty_id: T::KittyIndex, opponent_id: T::KittyIndex, wager: BalanceOf<T, I>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::usable_kitty(&sender, kitty_id)?;
+			ensure!(kitty_id != opponent_id, Error::<T, I>::CannotFightSelf);
+			let opponent_user = Self::kitty_user(opponent_id).ok_or(Error::<T, I>::KittyNotOwned)?;
+			ensure!(opponent_user != sender, Error::<T, I>::CannotFightSelf);
+			ensure!(!Challenges::<T, I>::contains_key(kitty_id), Error::<T, I>::ChallengeExists);
+			T::Currency::reserve(&sender, wager)?;
+			Challenges::<T, I>::insert(kitty_id, Challenge { owner: sender.clone(), opponent: opponent_id, wager });
+			Self::deposit_event(RawEvent::BattleChallenged(sender, kitty_id, opponent_id, wager));
+			Ok(())
+		}
+
+		/// Withdraw a challenge, refunding the wager
+		#[weight = 1000]
+		pub fn cancel_challenge(origin, kitty_id: T::KittyIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let challenge = Self::challenges(kitty_id).ok_or(Error::<T, I>::ChallengeNotFound)?;
+			ensure!(challenge.owner == sender, Error::<T, I>::KittyNotOwned);
+			Challenges::<T, I>::remove(kitty_id);
+			T::Currency::unreserve(&sender, challenge.wager);
+			Self::deposit_event(RawEvent::BattleChallengeCancelled(sender, kitty_id));
+			Ok(())
+		}
+
+		/// Accept a challenge to own kitty, matching the wager. The battle is fought right away,
+		/// the winner gains experience and its owner takes both wagers
+		#[weight = 1000]
+		#[transactional]
+		pub fn accept_challenge(origin, kitty_id: T::KittyIndex, challenger_id: T::KittyIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let opponent = Self::usable_kitty(&sender, kitty_id)?;
+			let challenge = Self::challenges(challenger_id)
+				.filter(|c| c.opponent == kitty_id)
+				.ok_or(Error::<T, I>::ChallengeNotFound)?;
+			ensure!(challenge.owner != sender, Error::<T, I>::CannotFightSelf);
+			// challenge goes stale if the challenger has changed hands, or its rental ended, since
+			let challenger = Self::usable_kitty(&challenge.owner, challenger_id).map_err(|_| Error::<T, I>::ChallengeNotFound)?;
+			T::Currency::reserve(&sender, challenge.wager)?;
+			Challenges::<T, I>::remove(challenger_id);
+
+			let outcome = battle::fight(Stats::from_dna(&challenger.0), Stats::from_dna(&opponent.0), Self::random_value(&sender));
+			let (winner_id, winner, loser) = if outcome.challenger_wins {
+				(challenger_id, challenge.owner, sender)
+			} else {
+				(kitty_id, sender, challenge.owner)
+			};
+			Self::gain_experience(winner_id, battle::WIN_EXPERIENCE);
+			Self::deposit_event(RawEvent::BattleFought(challenger_id, kitty_id, winner_id, outcome.strikes));
+
+			if !challenge.wager.is_zero() {
+				T::Currency::unreserve(&winner, challenge.wager);
+				T::Currency::repatriate_reserved(&loser, &winner, challenge.wager, BalanceStatus::Free)?;
+				Self::deposit_event(RawEvent::WagerWon(winner, loser, challenge.wager));
+			}
+			Ok(())
+		}
+
+		/// Create a knockout tournament for up to `bracket_size` kitties, registration closes at `deadline`
+		#[weight = 1000]
+		pub fn create_tournament(origin, entry_fee: BalanceOf<T, I>, deadline: T::BlockNumber, bracket_size: u32) -> DispatchResult {
+			T::TournamentOrigin::ensure_origin(origin)?;
+			ensure!(
+				bracket_size >= 2 && bracket_size <= T::MaxBracketSize::get() && bracket_size.is_power_of_two(),
+				Error::<T, I>::InvalidBracketSize
+			);
+			ensure!(deadline > <frame_system::Module<T>>::block_number(), Error::<T, I>::InvalidDeadline);
+			// entry fees below the existential deposit can't open the prize pool account
+			ensure!(entry_fee.is_zero() || entry_fee >= T::Currency::minimum_balance(), Error::<T, I>::EntryFeeTooLow);
+			ensure!((Self::active_tournaments().len() as u32) < T::MaxActiveTournaments::get(), Error::<T, I>::TooManyTournaments);
+			let tournament_id = Self::next_tournament_id();
+			NextTournamentId::<I>::put(tournament_id.saturating_add(1));
+			Tournaments::<T, I>::insert(tournament_id, Tournament {
+				entry_fee,
+				deadline,
+				bracket_size,
+				entrants: Vec::new(),
+				remaining: Vec::new(),
+				round: 0,
+				status: TournamentStatus::Registration,
+				winner: None,
+			});
+			ActiveTournaments::<I>::append(tournament_id);
+			Self::deposit_event(RawEvent::TournamentCreated(tournament_id, entry_fee, deadline, bracket_size));
+			Ok(())
+		}
+
+		/// Enter own kitty into a tournament, paying the entry fee into the prize pool
+		#[weight = 1000]
+		#[transactional]
+		pub fn enter_tournament(origin, tournament_id: u32, kitty_id: T::KittyIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::usable_kitty(&sender, kitty_id)?;
+			Tournaments::<T, I>::try_mutate(tournament_id, |tournament| -> DispatchResult {
+				let tournament = tournament.as_mut().ok_or(Error::<T, I>::TournamentNotFound)?;
+				ensure!(
+					tournament.status == TournamentStatus::Registration
+						&& <frame_system::Module<T>>::block_number() < tournament.deadline,
+					Error::<T, I>::TournamentRegistrationClosed
+				);
+				ensure!((tournament.entrants.len() as u32) < tournament.bracket_size, Error::<T, I>::TournamentFull);
+				ensure!(!tournament.entrants.iter().any(|(id, _)| *id == kitty_id), Error::<T, I>::AlreadyEntered);
+				T::Currency::transfer(&sender, &Self::tournament_account(tournament_id), tournament.entry_fee, ExistenceRequirement::KeepAlive)?;
+				tournament.entrants.push((kitty_id, sender.clone()));
+				Ok(())
+			})?;
+			Self::deposit_event(RawEvent::TournamentEntered(sender, tournament_id, kitty_id));
+			Ok(())
+		}
+
+		/// Offer to lend own kitty to `borrower` for `duration` blocks, for a fee
+		#[weight = 1000]
+		pub fn lend(origin, kitty_id: T::KittyIndex, borrower: T::AccountId, duration: T::BlockNumber, fee: BalanceOf<T, I>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(Kitties::<T, I>::contains_key(&sender, kitty_id), Error::<T, I>::KittyNotOwned);
+			ensure!(!Rentals::<T, I>::contains_key(kitty_id), Error::<T, I>::KittyLent);
+			Self::ensure_not_collateral(kitty_id)?;
+			ensure!(sender != borrower, Error::<T, I>::CannotLendToSelf);
+			LendOffers::<T, I>::insert(kitty_id, LendOffer { owner: sender.clone(), borrower: borrower.clone(), duration, fee });
+			Self::deposit_event(RawEvent::KittyLendOffered(sender, kitty_id, borrower, duration, fee));
+			Ok(())
+		}
+
+		#[weight = 1000]
+		pub fn cancel_lend(origin, kitty_id: T::KittyIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let offer = Self::lend_offers(kitty_id).ok_or(Error::<T, I>::LendOfferNotFound)?;
+			ensure!(offer.owner == sender, Error::<T, I>::KittyNotOwned);
+			LendOffers::<T, I>::remove(kitty_id);
+			Self::deposit_event(RawEvent::KittyLendCancelled(sender, kitty_id));
+			Ok(())
+		}
+
+		/// Borrow a kitty offered to sender, paying the fee to its owner. The kitty can be bred and
+		/// battled with until the rental expires, but stays its owner's
+		#[weight = 1000]
+		#[transactional]
+		pub fn borrow(origin, kitty_id: T::KittyIndex, max_fee: BalanceOf<T, I>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let offer = Self::lend_offers(kitty_id)
+				.filter(|offer| offer.borrower == sender)
+				.ok_or(Error::<T, I>::LendOfferNotFound)?;
+			// offer goes stale if the kitty has changed hands since
+			ensure!(Kitties::<T, I>::contains_key(&offer.owner, kitty_id), Error::<T, I>::LendOfferNotFound);
+			// or has been locked as collateral since
+			Self::ensure_not_collateral(kitty_id)?;
+			ensure!(offer.fee <= max_fee, Error::<T, I>::KittyPriceTooLow);
+
+			T::Currency::transfer(&sender, &offer.owner, offer.fee, ExistenceRequirement::KeepAlive)?;
+			let expires = <frame_system::Module<T>>::block_number()
+				.saturating_add(offer.duration.max(One::one()));
+			LendOffers::<T, I>::remove(kitty_id);
+			Rentals::<T, I>::insert(kitty_id, Rental { owner: offer.owner.clone(), borrower: sender.clone(), expires });
+			RentalExpiries::<T, I>::append(expires, kitty_id);
+			Self::deposit_event(RawEvent::KittyBorrowed(offer.owner, sender, kitty_id, offer.fee, expires));
+			Ok(())
+		}
+
+		/// Request a loan of `amount` against own kitty, to be repaid with `interest` by the `due` block
+		#[weight = 1000]
+		pub fn request_loan(origin, kitty_id: T::KittyIndex, amount: BalanceOf<T, I>, interest: BalanceOf<T, I>, due: T::BlockNumber) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(Kitties::<T, I>::contains_key(&sender, kitty_id), Error::<T, I>::KittyNotOwned);
+			ensure!(!Loans::<T, I>::contains_key(kitty_id), Error::<T, I>::LoanExists);
+			ensure!(!Rentals::<T, I>::contains_key(kitty_id), Error::<T, I>::KittyLent);
+			// collateral must be claimable
+			Self::ensure_transferable(kitty_id)?;
+			ensure!(due > <frame_system::Module<T>>::block_number(), Error::<T, I>::InvalidDeadline);
+			Loans::<T, I>::insert(kitty_id, Loan { borrower: sender.clone(), lender: None, amount, interest, due });
+			Self::deposit_event(RawEvent::LoanRequested(sender, kitty_id, amount, interest, due));
+			Ok(())
+		}
+
+		#[weight = 1000]
+		pub fn cancel_loan_request(origin, kitty_id: T::KittyIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let loan = Self::loans(kitty_id).ok_or(Error::<T, I>::LoanNotFound)?;
+			ensure!(loan.borrower == sender, Error::<T, I>::KittyNotOwned);
+			ensure!(loan.lender.is_none(), Error::<T, I>::LoanAlreadyFunded);
+			Loans::<T, I>::remove(kitty_id);
+			Self::deposit_event(RawEvent::LoanRequestCancelled(sender, kitty_id));
+			Ok(())
+		}
+
+		/// Fund a requested loan on its terms, locking the kitty until repaid or claimed
+		#[weight = 1000]
+		#[transactional]
+		pub fn fund_loan(origin, kitty_id: T::KittyIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let mut loan = Self::loans(kitty_id).ok_or(Error::<T, I>::LoanNotFound)?;
+			ensure!(loan.lender.is_none(), Error::<T, I>::LoanAlreadyFunded);
+			// request goes stale if the kitty has changed hands since
+			ensure!(Kitties::<T, I>::contains_key(&loan.borrower, kitty_id), Error::<T, I>::LoanNotFound);
+			ensure!(loan.borrower != sender, Error::<T, I>::CannotLendToSelf);
+			ensure!(loan.due > <frame_system::Module<T>>::block_number(), Error::<T, I>::LoanOverdue);
+			// collateral must be claimable, lent kitties can't be moved until returned
+			ensure!(!Rentals::<T, I>::contains_key(kitty_id), Error::<T, I>::KittyLent);
+
+			T::Currency::transfer(&sender, &loan.borrower, loan.amount, ExistenceRequirement::KeepAlive)?;
+			loan.lender = Some(sender.clone());
+			Self::deposit_event(RawEvent::LoanFunded(sender, loan.borrower.clone(), kitty_id, loan.amount));
+			Loans::<T, I>::insert(kitty_id, loan);
+			Ok(())
+		}
+
+		/// Repay a loan with interest by its due block, unlocking the kitty
+		#[weight = 1000]
+		#[transactional]
+		pub fn repay_loan(origin, kitty_id: T::KittyIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let loan = Self::loans(kitty_id).ok_or(Error::<T, I>::LoanNotFound)?;
+			ensure!(loan.borrower == sender, Error::<T, I>::KittyNotOwned);
+			let lender = loan.lender.ok_or(Error::<T, I>::LoanNotFound)?;
+			ensure!(<frame_system::Module<T>>::block_number() <= loan.due, Error::<T, I>::LoanOverdue);
+
+			let repaid = loan.amount.saturating_add(loan.interest);
+			T::Currency::transfer(&sender, &lender, repaid, ExistenceRequirement::KeepAlive)?;
+			Loans::<T, I>::remove(kitty_id);
+			Self::deposit_event(RawEvent::LoanRepaid(sender, lender, kitty_id, repaid));
+			Ok(())
+		}
+
+		/// Claim the kitty of a loan not repaid by its due block, even if its collection has been made
+		/// non transferable since
+		#[weight = 1000]
+		#[transactional]
+		pub fn claim_collateral(origin, kitty_id: T::KittyIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let loan = Self::loans(kitty_id).ok_or(Error::<T, I>::LoanNotFound)?;
+			ensure!(loan.lender.as_ref() == Some(&sender), Error::<T, I>::LoanNotFound);
+			ensure!(<frame_system::Module<T>>::block_number() > loan.due, Error::<T, I>::LoanNotOverdue);
+
+			Loans::<T, I>::remove(kitty_id);
+			Self::move_kitty(&loan.borrower, &sender, kitty_id)?;
+			Self::deposit_event(RawEvent::CollateralClaimed(sender, loan.borrower, kitty_id));
+			Ok(())
+		}
+
+		/// Lock own kitty in a vault account, issuing `shares` of it to sender
+		#[weight = 1000]
+		#[transactional]
+		pub fn vault(origin, kitty_id: T::KittyIndex, shares: u64) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(shares > 0, Error::<T, I>::InvalidShares);
+			Self::do_transfer(&sender, &Self::vault_account(kitty_id), kitty_id)?;
+			Prices::<T, I>::remove(kitty_id);
+			PriceAssets::<T, I>::remove(kitty_id);
+			Vaults::<T, I>::insert(kitty_id, shares);
+			Shares::<T, I>::insert(kitty_id, &sender, shares);
+			Self::deposit_event(RawEvent::KittyVaulted(sender, kitty_id, shares));
+			Ok(())
+		}
+
+		#[weight = 1000]
+		pub fn transfer_shares(origin, kitty_id: T::KittyIndex, to: T::AccountId, amount: u64) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(Vaults::<T, I>::contains_key(kitty_id), Error::<T, I>::VaultNotFound);
+			Shares::<T, I>::try_mutate_exists(kitty_id, &sender, |balance| -> DispatchResult {
+				let remaining = balance.unwrap_or_default().checked_sub(amount).ok_or(Error::<T, I>::InsufficientShares)?;
+				*balance = Some(remaining).filter(|shares| *shares > 0);
+				Ok(())
+			})?;
+			Shares::<T, I>::mutate(kitty_id, &to, |balance| *balance = balance.saturating_add(amount));
+			Self::deposit_event(RawEvent::SharesTransferred(sender, to, kitty_id, amount));
+			Ok(())
+		}
+
+		/// Redeem a vaulted kitty, requires holding all of its shares. Redemption doesn't depend on
+		/// the kitty's collection staying transferable
+		#[weight = 1000]
+		#[transactional]
+		pub fn redeem(origin, kitty_id: T::KittyIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let shares = Self::vaults(kitty_id).ok_or(Error::<T, I>::VaultNotFound)?;
+			ensure!(Self::shares(kitty_id, &sender) == shares, Error::<T, I>::InsufficientShares);
+			Vaults::<T, I>::remove(kitty_id);
+			Shares::<T, I>::remove(kitty_id, &sender);
+			Self::move_kitty(&Self::vault_account(kitty_id), &sender, kitty_id)?;
+			Self::deposit_event(RawEvent::KittyRedeemed(sender, kitty_id));
+			Ok(())
+		}
+
+		/// Transfer many kitties, as \[new_owner, kitty_id\] pairs
+		#[weight = (transfers.len() as Weight).saturating_mul(1000)]
+		#[transactional]
+		pub fn transfer_batch(origin, transfers: Vec<(T::AccountId, T::KittyIndex)>, mode: BatchMode) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::run_batch(mode, transfers, |(new_owner, kitty_id)| Self::do_transfer(&sender, &new_owner, kitty_id))
+		}
+
+		/// Set prices of many kitties, as \[kitty_id, new_price\] pairs
+		#[weight = (prices.len() as Weight).saturating_mul(1000)]
+		#[transactional]
+		pub fn set_price_batch(origin, prices: Vec<(T::KittyIndex, Option<BalanceOf<T, I>>)>, mode: BatchMode) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::run_batch(mode, prices, |(kitty_id, new_price)| Self::do_set_price(&sender, kitty_id, new_price))
+		}
+
+		/// Breed many couples, as \[parent1_id, parent2_id\] pairs
+		#[weight = (couples.len() as Weight).saturating_mul(1000)]
+		#[transactional]
+		pub fn breed_batch(origin, couples: Vec<(T::KittyIndex, T::KittyIndex)>, mode: BatchMode) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::run_batch(mode, couples, |(parent1_id, parent2_id)| Self::do_breed(&sender, parent1_id, parent2_id))
+		}
+
+		/// Burn two own kitties to mint a new one, inheriting the stronger of their fighting stats
+		#[weight = 1000]
+		#[transactional]
+		pub fn fuse(origin, kitty_a: T::KittyIndex, kitty_b: T::KittyIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(kitty_a != kitty_b, Error::<T, I>::CannotFuseSelf);
+			let a = Self::kitties(&sender, kitty_a).ok_or(Error::<T, I>::KittyNotOwned)?;
+			let b = Self::kitties(&sender, kitty_b).ok_or(Error::<T, I>::KittyNotOwned)?;
+			// the fused kitty is free to change hands, its inputs must be too
+			Self::ensure_transferable(kitty_a)?;
+			Self::ensure_transferable(kitty_b)?;
+
+			let random = Self::random_value(&sender);
+			let dna = Self::unique_dna(|attempt| mix_dna(bias_to_stronger(reroll(random, attempt), a.0, b.0), a.0, b.0))
+				.ok_or(Error::<T, I>::DuplicateDna)?;
+			Self::do_burn(&sender, kitty_a)?;
+			Self::do_burn(&sender, kitty_b)?;
+			let kitty_id = Self::mint(&sender, &sender, dna)?;
+			FusedFrom::<T, I>::insert(kitty_id, (kitty_a, kitty_b));
+			Self::deposit_event(RawEvent::KittiesFused(sender, kitty_a, kitty_b, kitty_id));
+			Ok(())
+		}
+
+		/// Release a kitty for good, refunding its deposit
+		#[weight = 1000]
+		pub fn burn(origin, kitty_id: T::KittyIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::do_burn(&sender, kitty_id)
+		}
+
 	}
 }
 
 // from Bryan's answers
-impl<T: Config> Module<T> {
+impl<T: Config<I>, I: Instance> Module<T, I> {
 	fn get_next_kitty_id() -> sp_std::result::Result<T::KittyIndex, DispatchError> {
-		NextKittyId::<T>::try_mutate(|next_id| -> sp_std::result::Result<T::KittyIndex, DispatchError> {
+		NextKittyId::<T, I>::try_mutate(|next_id| -> sp_std::result::Result<T::KittyIndex, DispatchError> {
 			let current_id = *next_id;
-			*next_id = next_id.checked_add(&One::one()).ok_or(Error::<T>::KittiesIdOverflow)?;
+			*next_id = next_id.checked_add(&One::one()).ok_or(Error::<T, I>::KittiesIdOverflow)?;
 			Ok(current_id)
 		})
 	}
 
+	/// First DNA not held by any kitty, out of `generate(0)` and up to `MaxDnaRetries` retries
+	fn unique_dna(generate: impl Fn(u32) -> [u8; 16]) -> Option<[u8; 16]> {
+		(0..=T::MaxDnaRetries::get())
+			.map(generate)
+			.find(|dna| !DnaKitties::<T, I>::contains_key(dna))
+	}
+
+	/// Track trait occurrences of a kitty coming to life, or burned
+	fn count_traits(dna: &[u8; 16], alive: bool) {
+		let update = |count: &mut u32| {
+			*count = if alive { count.saturating_add(1) } else { count.saturating_sub(1) };
+		};
+		for (gene, value) in genes::decode(dna).iter() {
+			TraitCounts::<I>::mutate(gene, value, update);
+		}
+		KittyCount::<I>::mutate(update);
+	}
+
+	/// Rarity score of the kitty, the sum over its traits of the inverse trait frequency among live kitties,
+	/// in percent. A kitty with all unique traits scores `100 * kitty_count()` per gene
+	pub fn rarity(kitty_id: T::KittyIndex) -> Option<u32> {
+		let kitty = KittyOwner::<T, I>::get(kitty_id).and_then(|owner| Self::kitties(owner, kitty_id))?;
+		let total = Self::kitty_count().saturating_mul(100);
+		Some(genes::decode(&kitty.0).iter()
+			.map(|(gene, value)| total / Self::trait_count(gene, value).max(1))
+			.fold(0, |score: u32, rarity| score.saturating_add(rarity)))
+	}
+
+	/// Mint a new kitty for `owner`, deposit is reserved from `depositor`. DNA must be unique
+	fn mint(depositor: &T::AccountId, owner: &T::AccountId, dna: [u8; 16]) -> sp_std::result::Result<T::KittyIndex, DispatchError> {
+		// ensure no id overflow
+		let kitty_id = Self::get_next_kitty_id()?;
+		T::OnTransfer::can_transfer(None, Some(owner), &kitty_id)?;
+		Self::reserve_deposit(depositor, kitty_id)?;
+
+		// Create and store kitty
+		let kitty = Kitty(dna);
+		// note, setter isn't created as part of doublemap decl_storage!
+		Kitties::<T, I>::insert(owner, kitty_id, kitty.clone());
+		KittyOwner::<T, I>::insert(kitty_id, owner);
+		DnaKitties::<T, I>::insert(dna, kitty_id);
+		Self::count_traits(&dna, true);
+		T::OnTransfer::on_transfer(None, Some(owner), &kitty_id);
+
+		// Emit event
+		Self::deposit_event(RawEvent::KittyCreated(owner.clone(), kitty_id, kitty));
+
+		Ok(kitty_id)
+	}
+
+	/// The kitty, if `who` has the right to breed and battle with it: its owner unless lent out, or its borrower
+	fn usable_kitty(who: &T::AccountId, kitty_id: T::KittyIndex) -> sp_std::result::Result<Kitty, DispatchError> {
+		let owner = match Self::rentals(kitty_id) {
+			Some(rental) if rental.borrower == *who => rental.owner,
+			Some(_) => return Err(Error::<T, I>::KittyLent.into()),
+			None => who.clone(),
+		};
+		Self::kitties(&owner, kitty_id).ok_or_else(|| Error::<T, I>::KittyNotOwned.into())
+	}
+
+	/// Account with the right to breed and battle with the kitty: its borrower if lent out, else its owner
+	fn kitty_user(kitty_id: T::KittyIndex) -> Option<T::AccountId> {
+		Self::rentals(kitty_id).map(|rental| rental.borrower).or_else(|| Self::kitty_owner(kitty_id))
+	}
+
+	/// Withdraw the kitty's transfer offer, along with its entry in the expiry index
+	fn remove_transfer_offer(kitty_id: T::KittyIndex) {
+		if let Some(offer) = TransferOffers::<T, I>::take(kitty_id) {
+			let mut expiring = TransferOfferExpiries::<T, I>::take(offer.expires);
+			expiring.retain(|id| *id != kitty_id);
+			if !expiring.is_empty() {
+				TransferOfferExpiries::<T, I>::insert(offer.expires, expiring);
+			}
+		}
+	}
+
+	/// Withdraw any challenge issued by the kitty, refunding the wager
+	fn drop_challenge(kitty_id: T::KittyIndex) {
+		if let Some(challenge) = Challenges::<T, I>::take(kitty_id) {
+			T::Currency::unreserve(&challenge.owner, challenge.wager);
+		}
+	}
+
+	/// Kitties locked as collateral of a funded loan can't change hands until the loan is repaid or claimed
+	fn ensure_not_collateral(kitty_id: T::KittyIndex) -> DispatchResult {
+		let locked = Self::loans(kitty_id).map_or(false, |loan| loan.lender.is_some());
+		ensure!(!locked, Error::<T, I>::KittyCollateralised);
+		Ok(())
+	}
+
+	/// Id of the kitty at `kitty_index` within the collection
+	fn collection_kitty_id(collection_id: T::CollectionId, kitty_index: u32) -> sp_std::result::Result<T::KittyIndex, DispatchError> {
+		Self::collection_kitties(collection_id, kitty_index).ok_or_else(|| Error::<T, I>::CollectionKittyNotFound.into())
+	}
+
+	/// Soulbound kitties and kitties of non transferable collections can't change hands, other than by burning
+	fn ensure_transferable(kitty_id: T::KittyIndex) -> DispatchResult {
+		ensure!(!Self::soulbound(kitty_id), Error::<T, I>::KittySoulbound);
+		if let Some((collection_id, _)) = Self::kitty_collection(kitty_id) {
+			let transferable = Self::collections(collection_id).map_or(true, |c| c.transferable);
+			ensure!(transferable, Error::<T, I>::CollectionNotTransferable);
+		}
+		Ok(())
+	}
+
+	/// Pay in the asset, or native currency if `None`
+	fn pay(asset: Option<T::AssetId>, from: &T::AccountId, to: &T::AccountId, amount: BalanceOf<T, I>) -> DispatchResult {
+		match asset {
+			Some(asset_id) => T::Assets::transfer(asset_id, from, to, amount),
+			None => T::Currency::transfer(from, to, amount, ExistenceRequirement::KeepAlive),  // KeepAlive = ensure enough funds in account to keep account alive
+		}
+	}
+
+	fn do_breed(sender: &T::AccountId, parent1_id: T::KittyIndex, parent2_id: T::KittyIndex) -> DispatchResult {
+		let parent1 = Self::usable_kitty(sender, parent1_id)?;
+		let parent2 = Self::usable_kitty(sender, parent2_id)?;
+		let (momma, pappa) = get_female_male(&parent1, &parent2).ok_or(Error::<T, I>::KittiesBredFromSameGenderCouple)?;
+		// ensure recording tuple order: momma, pappa
+		let (momma_id, poppa_id) = if parent1.get_gender() == Gender::Female {
+			(parent1_id, parent2_id)
+		} else {
+			(parent2_id, parent1_id)
+		};
+		Self::conceive(sender, momma_id, momma, poppa_id, pappa)
+	}
+
+	fn do_set_price(sender: &T::AccountId, kitty_id: T::KittyIndex, new_price: Option<BalanceOf<T, I>>) -> DispatchResult {
+		ensure!(Kitties::<T, I>::contains_key(sender, kitty_id), Error::<T, I>::KittyNotOwned);  // more performant than fetch cause no serialization
+		if new_price.is_some() {
+			Self::ensure_transferable(kitty_id)?;
+		}
+		Prices::<T, I>::mutate_exists(kitty_id, |price| -> () {
+			*price = new_price;
+		});  // if returning a None, force erasue
+		PriceAssets::<T, I>::remove(kitty_id);
+		Self::deposit_event(RawEvent::KittyPriceSet(sender.clone(), kitty_id, new_price));
+		Ok(())
+	}
+
+	/// Run batch items in turn, each in its own storage transaction, emitting each item's result.
+	/// All or nothing batches stop at the first failure, the whole call reverting
+	fn run_batch<Item>(mode: BatchMode, items: Vec<Item>, run: impl Fn(Item) -> DispatchResult) -> DispatchResult {
+		ensure!(items.len() <= T::MaxBatchSize::get() as usize, Error::<T, I>::BatchTooLarge);
+		for (index, item) in items.into_iter().enumerate() {
+			let result = with_transaction(|| {
+				let result = run(item);
+				if result.is_ok() {
+					TransactionOutcome::Commit(result)
+				} else {
+					TransactionOutcome::Rollback(result)
+				}
+			});
+			Self::deposit_event(RawEvent::BatchItemCompleted(index as u32, result));
+			if mode == BatchMode::AllOrNothing {
+				result?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Start a pregnancy, kitten is due `GestationPeriod` blocks from now
+	fn conceive(owner: &T::AccountId, momma_id: T::KittyIndex, momma: &Kitty, papa_id: T::KittyIndex, papa: &Kitty) -> DispatchResult {
+		ensure!(!Pregnancies::<T, I>::contains_key(momma_id), Error::<T, I>::KittyAlreadyPregnant);
+
+		let child_id = Self::get_next_kitty_id()?;
+		// kitten is minted at birth, hooks get to veto it now
+		T::OnTransfer::can_transfer(None, Some(owner), &child_id)?;
+		Self::reserve_deposit(owner, child_id)?;
+		let due = <frame_system::Module<T>>::block_number()
+			.saturating_add(T::GestationPeriod::get().max(One::one()));
+
+		Pregnancies::<T, I>::insert(momma_id, Pregnancy {
+			owner: owner.clone(),
+			child_id,
+			papa_id,
+			momma: momma.clone(),
+			papa: papa.clone(),
+			due,
+		});
+		Births::<T, I>::append(due, momma_id);
+		Self::gain_experience(momma_id, evolution::BREED_EXPERIENCE);
+		Self::gain_experience(papa_id, evolution::BREED_EXPERIENCE);
+
+		frame_support::debug::RuntimeLogger::init();
+		frame_support::debug::info!("##### conceive(): child_id: {}, momma_id: {}, papa_id: {}", child_id, momma_id, papa_id);
+
+		Self::deposit_event(RawEvent::KittyBred(owner.clone(), child_id, momma_id, papa_id, due));
+
+		Ok(())
+	}
+
+	/// Move the kitty to a new owner, dropping any approval. Transfer to self is a no-op
+	fn do_transfer(owner: &T::AccountId, new_owner: &T::AccountId, kitty_id: T::KittyIndex) -> DispatchResult {
+		ensure!(Kitties::<T, I>::contains_key(owner, kitty_id), Error::<T, I>::KittyNotOwned);
+		if owner != new_owner {
+			Self::ensure_transferable(kitty_id)?;
+		}
+		Self::move_kitty(owner, new_owner, kitty_id)
+	}
+
+	/// Move the kitty regardless of its transferability. Settles kitties locked while transferable,
+	/// collection settings changed since mustn't strand them
+	fn move_kitty(owner: &T::AccountId, new_owner: &T::AccountId, kitty_id: T::KittyIndex) -> DispatchResult {
+		Kitties::<T, I>::try_mutate_exists(owner, kitty_id, |kitty| -> DispatchResult {
+			if owner == new_owner && kitty.is_some() {
+				Ok(())
+			} else {
+				match kitty.take() {  // not sure why, but take() is required to remove from storage
+					None    => Err(Error::<T, I>::KittyNotOwned.into()),
+					Some(k) => {
+						ensure!(!Rentals::<T, I>::contains_key(kitty_id), Error::<T, I>::KittyLent);
+						Self::ensure_not_collateral(kitty_id)?;
+						ensure!(!Vaults::<T, I>::contains_key(kitty_id), Error::<T, I>::KittyVaulted);
+						T::OnTransfer::can_transfer(Some(owner), Some(new_owner), &kitty_id)?;
+						Kitties::<T, I>::insert(new_owner, kitty_id, k.clone());
+						KittyOwner::<T, I>::insert(kitty_id, new_owner);
+						// Prices::<T, I>::insert(kitty_id, None);
+						Approvals::<T, I>::remove(kitty_id);
+						Self::remove_transfer_offer(kitty_id);
+						// only unfunded loan requests are left, the new owner didn't make them
+						Loans::<T, I>::remove(kitty_id);
+						Self::drop_challenge(kitty_id);
+						T::OnTransfer::on_transfer(Some(owner), Some(new_owner), &kitty_id);
+						Self::deposit_event(RawEvent::KittyTransfered(owner.clone(), new_owner.clone(), kitty_id, k));
+						Ok(())
+					}
+				}
+			}
+		})
+	}
+
+	/// Remove the kitty along with its listing and own lineage record
+	fn do_burn(owner: &T::AccountId, kitty_id: T::KittyIndex) -> DispatchResult {
+		let kitty = Self::kitties(owner, kitty_id).ok_or(Error::<T, I>::KittyNotOwned)?;
+		ensure!(!Rentals::<T, I>::contains_key(kitty_id), Error::<T, I>::KittyLent);
+		Self::ensure_not_collateral(kitty_id)?;
+		T::OnTransfer::can_transfer(Some(owner), None, &kitty_id)?;
+		Kitties::<T, I>::remove(owner, kitty_id);
+		DnaKitties::<T, I>::remove(kitty.0);
+		Self::count_traits(&kitty.0, false);
+		KittyOwner::<T, I>::remove(kitty_id);
+		Prices::<T, I>::remove(kitty_id);
+		PriceAssets::<T, I>::remove(kitty_id);
+		SiringOffers::<T, I>::remove(kitty_id);
+		Approvals::<T, I>::remove(kitty_id);
+		Parents::<T, I>::remove(kitty_id);
+		FusedFrom::<T, I>::remove(kitty_id);
+		LendOffers::<T, I>::remove(kitty_id);
+		Self::remove_transfer_offer(kitty_id);
+		Loans::<T, I>::remove(kitty_id);
+		KittyMetadata::<T, I>::remove(kitty_id);
+		Progress::<T, I>::remove(kitty_id);
+		Self::drop_challenge(kitty_id);
+		Soulbound::<T, I>::remove(kitty_id);
+		if let Some((collection_id, kitty_index)) = KittyCollection::<T, I>::take(kitty_id) {
+			CollectionKitties::<T, I>::remove(collection_id, kitty_index);
+		}
+		if let Some((depositor, deposit)) = Deposits::<T, I>::take(kitty_id) {
+			T::Currency::unreserve(&depositor, deposit);
+		}
+		for (_key, attribute) in Attributes::<T, I>::drain_prefix(kitty_id) {
+			T::Currency::unreserve(&attribute.depositor, attribute.deposit);
+		}
+		if let Some(name) = Self::release_name(kitty_id) {
+			Self::deposit_event(RawEvent::KittyNameCleared(owner.clone(), kitty_id, name));
+		}
+		T::OnTransfer::on_transfer(Some(owner), None, &kitty_id);
+		Self::deposit_event(RawEvent::KittyBurned(owner.clone(), kitty_id));
+		Ok(())
+	}
+
+	fn update_metadata(metadata: &mut Option<Metadata<T::Hash>>, uri: Vec<u8>, hash: T::Hash) -> DispatchResult {
+		ensure!(uri.len() <= T::UriLimit::get() as usize, Error::<T, I>::UriTooLong);
+		ensure!(!metadata.as_ref().map_or(false, |m| m.frozen), Error::<T, I>::MetadataIsFrozen);
+		*metadata = Some(Metadata { uri, hash, frozen: false });
+		Ok(())
+	}
+
+	fn freeze(metadata: &mut Option<Metadata<T::Hash>>) -> DispatchResult {
+		let metadata = metadata.as_mut().ok_or(Error::<T, I>::MetadataNotFound)?;
+		ensure!(!metadata.frozen, Error::<T, I>::MetadataIsFrozen);
+		metadata.frozen = true;
+		Ok(())
+	}
+
+	/// Kitty's own metadata, falling back to its collection's
+	pub fn metadata_of(kitty_id: T::KittyIndex) -> Option<Metadata<T::Hash>> {
+		Self::kitty_metadata(kitty_id).or_else(|| {
+			Self::kitty_collection(kitty_id).and_then(|(collection_id, _)| Self::collection_metadata(collection_id))
+		})
+	}
+
+	/// Add experience to the kitty, evolving it for every level reached
+	fn gain_experience(kitty_id: T::KittyIndex, experience: u32) {
+		let (from, to) = Progress::<T, I>::mutate(kitty_id, |progress| {
+			let from = progress.level;
+			progress.experience = progress.experience.saturating_add(experience);
+			progress.level = evolution::level(progress.experience).max(from);
+			(from, progress.level)
+		});
+		for level in from.saturating_add(1)..=to {
+			Self::evolve(kitty_id, level);
+		}
+	}
+
+	/// Evolve the kitty's DNA for the level. DNA stays as is if the evolved DNA is already taken
+	fn evolve(kitty_id: T::KittyIndex, level: u8) {
+		let owner = match Self::kitty_owner(kitty_id) {
+			Some(owner) => owner,
+			None => return,
+		};
+		if let Some(kitty) = Self::kitties(&owner, kitty_id) {
+			let dna = evolution::evolve(kitty.0, level);
+			let kitty = if DnaKitties::<T, I>::contains_key(dna) {
+				kitty
+			} else {
+				DnaKitties::<T, I>::remove(kitty.0);
+				DnaKitties::<T, I>::insert(dna, kitty_id);
+				Kitties::<T, I>::insert(&owner, kitty_id, Kitty(dna));
+				Kitty(dna)
+			};
+			Self::deposit_event(RawEvent::KittyEvolved(owner, kitty_id, level, kitty));
+		}
+	}
+
+	/// Kitties minted before `Releases::V2` are missing from the indexes kept alongside `Kitties`, rebuild them.
+	/// Kitties already sharing DNA keep it, the DNA index holds one of them. Trait counts are recounted from scratch
+	fn migrate_to_v2() -> Weight {
+		let mut migrated: Weight = 0;
+		// per gene, in `GENES` order, counts of each value. Genes have at most 8 variants
+		let mut counts = [[0u32; 8]; 6];
+		for (owner, kitty_id, kitty) in Kitties::<T, I>::iter() {
+			KittyOwner::<T, I>::insert(kitty_id, owner);
+			DnaKitties::<T, I>::insert(kitty.0, kitty_id);
+			for (i, (_, value)) in genes::decode(&kitty.0).iter().enumerate() {
+				counts[i][*value as usize] += 1;
+			}
+			migrated += 1;
+		}
+		let mut counted: Weight = 0;
+		for (i, gene) in genes::GENES.iter().enumerate() {
+			for value in 0..gene.variants() {
+				TraitCounts::<I>::insert(gene, value, counts[i][value as usize]);
+				counted += 1;
+			}
+		}
+		KittyCount::<I>::put(migrated as u32);
+		StorageVersion::<I>::put(Releases::V2);
+		T::DbWeight::get().reads_writes(migrated, 2 * migrated + counted + 2)
+	}
+
+	/// Account holding the vaulted kitty, only redemption moves the kitty out of it
+	pub fn vault_account(kitty_id: T::KittyIndex) -> T::AccountId {
+		T::ModuleId::get().into_sub_account((b"vault", kitty_id))
+	}
+
+	/// Account holding the tournament's prize pool
+	pub fn tournament_account(tournament_id: u32) -> T::AccountId {
+		T::ModuleId::get().into_sub_account(tournament_id)
+	}
+
+	/// Close registrations and fight the next round of active tournaments, returns the weight used
+	fn advance_tournaments(now: T::BlockNumber) -> Weight {
+		let mut active = Self::active_tournaments();
+		let mut weight = T::DbWeight::get().reads(1);
+		if !active.is_empty() {
+			active.retain(|tournament_id| {
+				let (still_active, used) = Self::advance_tournament(*tournament_id, now);
+				weight = weight.saturating_add(used);
+				still_active
+			});
+			ActiveTournaments::<I>::put(active);
+			weight = weight.saturating_add(T::DbWeight::get().writes(1));
+		}
+		weight
+	}
+
+	/// Close registration once past the deadline, then fight a round per block. Returns whether the
+	/// tournament is still active, and the weight used
+	fn advance_tournament(tournament_id: u32, now: T::BlockNumber) -> (bool, Weight) {
+		let db = T::DbWeight::get();
+		let mut tournament = match Self::tournaments(tournament_id) {
+			Some(tournament) => tournament,
+			None => return (false, db.reads(1)),
+		};
+		let pool = Self::tournament_account(tournament_id);
+
+		if tournament.status == TournamentStatus::Registration {
+			if now < tournament.deadline {
+				return (true, db.reads(1));
+			}
+			if tournament.entrants.len() < 2 {
+				for (_, payer) in tournament.entrants.iter() {
+					let _ = T::Currency::transfer(&pool, payer, tournament.entry_fee, ExistenceRequirement::AllowDeath);
+				}
+				let refunds = tournament.entrants.len() as Weight;
+				tournament.status = TournamentStatus::Cancelled;
+				Tournaments::<T, I>::insert(tournament_id, tournament);
+				Self::deposit_event(RawEvent::TournamentCancelled(tournament_id));
+				return (false, db.reads_writes(1 + 2 * refunds, 1 + 2 * refunds));
+			}
+			tournament.remaining = tournament.entrants.iter().map(|(kitty_id, _)| *kitty_id).collect();
+			tournament.status = TournamentStatus::InProgress;
+			Self::deposit_event(RawEvent::TournamentStarted(tournament_id, tournament.entrants.len() as u32));
+		}
+
+		// pair off remaining kitties, an odd one out gets a bye
+		let mut matches = Vec::new();
+		let mut next = Vec::new();
+		for pair in tournament.remaining.chunks(2) {
+			match pair {
+				[kitty_a, kitty_b] => {
+					let winner = Self::tournament_match(tournament_id, tournament.round, *kitty_a, *kitty_b);
+					matches.push((*kitty_a, *kitty_b, winner));
+					next.push(winner);
+				}
+				_ => next.extend_from_slice(pair),
+			}
+		}
+		let fought = matches.len() as Weight;
+		TournamentMatches::<T, I>::insert(tournament_id, tournament.round, matches);
+		tournament.remaining = next;
+		tournament.round = tournament.round.saturating_add(1);
+
+		if tournament.remaining.len() == 1 {
+			let winner = tournament.remaining[0];
+			// prize goes to the winner's current owner, or whoever entered it if burned since
+			let owner = KittyOwner::<T, I>::get(winner)
+				.or_else(|| tournament.entrants.iter().find(|(id, _)| *id == winner).map(|(_, payer)| payer.clone()));
+			if let Some(owner) = owner {
+				let prize = T::Currency::free_balance(&pool);
+				let _ = T::Currency::transfer(&pool, &owner, prize, ExistenceRequirement::AllowDeath);
+				Self::deposit_event(RawEvent::TournamentWon(tournament_id, winner, owner, prize));
+			}
+			tournament.status = TournamentStatus::Finished;
+			tournament.winner = Some(winner);
+		}
+		let active = tournament.status == TournamentStatus::InProgress;
+		Tournaments::<T, I>::insert(tournament_id, tournament);
+		// a match reads both kitties with their owners and the winner's progress, evolving the winner
+		// rewrites its DNA. Paying out the prize reads the winner's owner and moves the pool
+		(active, db.reads_writes(4 + 6 * fought, 5 + 4 * fought))
+	}
+
+	/// Fight a tournament match, the winner gains experience. Kitties burned since entering forfeit
+	fn tournament_match(tournament_id: u32, round: u32, kitty_a: T::KittyIndex, kitty_b: T::KittyIndex) -> T::KittyIndex {
+		let dna_of = |kitty_id| <Self as KittyRegistry<T::AccountId>>::kitty(&kitty_id);
+		let (a, b) = match (dna_of(kitty_a), dna_of(kitty_b)) {
+			(Some(a), Some(b)) => (a, b),
+			(_, None) => return kitty_a,
+			(None, Some(_)) => return kitty_b,
+		};
+		let random = (T::Randomness::random_seed(), tournament_id, round, kitty_a).using_encoded(blake2_128);
+		let outcome = battle::fight(Stats::from_dna(&a.0), Stats::from_dna(&b.0), random);
+		let winner = if outcome.challenger_wins { kitty_a } else { kitty_b };
+		Self::gain_experience(winner, battle::WIN_EXPERIENCE);
+		Self::deposit_event(RawEvent::TournamentMatch(tournament_id, round, kitty_a, kitty_b, winner, outcome.strikes));
+		winner
+	}
+
+	/// Free the kitty's name for others and refund its deposit, returns the released name
+	fn release_name(kitty_id: T::KittyIndex) -> Option<Vec<u8>> {
+		let name = Names::<T, I>::take(kitty_id)?;
+		NamedKitties::<T, I>::remove(&name.name);
+		T::Currency::unreserve(&name.depositor, name.deposit);
+		Some(name.name)
+	}
+
+	fn reserve_deposit(who: &T::AccountId, kitty_id: T::KittyIndex) -> DispatchResult {
+		let deposit = T::KittyDeposit::get();
+		if !deposit.is_zero() {
+			T::Currency::reserve(who, deposit)?;
+			Deposits::<T, I>::insert(kitty_id, (who.clone(), deposit));
+		}
+		Ok(())
+	}
+
+	/// Mint the kitten of a due pregnancy. Child DNA is mixed with randomness of the birth block.
+	/// Kitten isn't born if no unique DNA comes out of the mix
+	fn give_birth(momma_id: T::KittyIndex) {
+		if let Some(pregnancy) = Pregnancies::<T, I>::take(momma_id) {
+			let random: [u8; 16] = Self::birth_random_value(&pregnancy.owner, pregnancy.child_id);
+			let dna = match Self::unique_dna(|attempt| mix_dna(reroll(random, attempt), pregnancy.momma.0, pregnancy.papa.0)) {
+				Some(dna) => dna,
+				None => {
+					if let Some((depositor, deposit)) = Deposits::<T, I>::take(pregnancy.child_id) {
+						T::Currency::unreserve(&depositor, deposit);
+					}
+					Self::deposit_event(RawEvent::KittyBirthFailed(pregnancy.owner, pregnancy.child_id, momma_id));
+					return;
+				}
+			};
+			let child = Kitty(dna);
+			Kitties::<T, I>::insert(&pregnancy.owner, pregnancy.child_id, child.clone());
+			KittyOwner::<T, I>::insert(pregnancy.child_id, &pregnancy.owner);
+			DnaKitties::<T, I>::insert(dna, pregnancy.child_id);
+			Self::count_traits(&dna, true);
+			T::OnTransfer::on_transfer(None, Some(&pregnancy.owner), &pregnancy.child_id);
+			Parents::<T, I>::insert(pregnancy.child_id, (momma_id, pregnancy.papa_id));
+
+			frame_support::debug::RuntimeLogger::init();
+			frame_support::debug::info!("##### give_birth(): child dna: {:?}, child_id: {}", child.0, pregnancy.child_id);
+
+			Self::deposit_event(RawEvent::KittyBorn(pregnancy.owner, pregnancy.child_id, child));
+		}
+	}
+
 	fn random_value(sender: &T::AccountId) -> [u8; 16] {
 		let payload = (
 			T::Randomness::random_seed(),
@@ -243,6 +1992,48 @@ impl<T: Config> Module<T> {
 		);
 		payload.using_encoded(blake2_128)
 	}
+
+	/// Births happen outside of extrinsics, hence child id distinguishes kittens born in the same block
+	fn birth_random_value(owner: &T::AccountId, child_id: T::KittyIndex) -> [u8; 16] {
+		let payload = (
+			T::Randomness::random_seed(),
+			owner,
+			child_id,
+		);
+		payload.using_encoded(blake2_128)
+	}
+
+	/// Kitty details for the runtime API
+	pub fn kitty_details(kitty_id: T::KittyIndex) -> Option<KittyDetails<T::AccountId>> {
+		let owner = Self::kitty_owner(kitty_id)?;
+		let kitty = Self::kitties(&owner, kitty_id)?;
+		let attributes = Attributes::<T, I>::iter_prefix(kitty_id)
+			.map(|(key, attribute)| (key, attribute.value))
+			.collect();
+		Some(KittyDetails {
+			owner,
+			dna: kitty.0,
+			name: Self::names(kitty_id).map(|n| n.name),
+			attributes,
+		})
+	}
+}
+
+impl<T: Config<I>, I: Instance> KittyRegistry<T::AccountId> for Module<T, I> {
+	type KittyIndex = T::KittyIndex;
+
+	fn owner_of(kitty_id: &T::KittyIndex) -> Option<T::AccountId> {
+		KittyOwner::<T, I>::get(kitty_id)
+	}
+
+	fn kitty(kitty_id: &T::KittyIndex) -> Option<Kitty> {
+		KittyOwner::<T, I>::get(kitty_id).and_then(|owner| Kitties::<T, I>::get(owner, kitty_id))
+	}
+
+	fn transfer(kitty_id: &T::KittyIndex, new_owner: &T::AccountId) -> DispatchResult {
+		let owner = KittyOwner::<T, I>::get(kitty_id).ok_or(Error::<T, I>::KittyNotOwned)?;
+		Self::do_transfer(&owner, new_owner, *kitty_id)
+	}
 }
 
 fn get_female_male<'a>(kitty1: &'a Kitty, kitty2: &'a Kitty) -> Option<(&'a Kitty, &'a Kitty)> {
@@ -253,6 +2044,24 @@ fn get_female_male<'a>(kitty1: &'a Kitty, kitty2: &'a Kitty) -> Option<(&'a Kitt
 	}
 }
 
+/// Derive fresh randomness for the `attempt`th retry, the first attempt uses `random` as is
+fn reroll(random: [u8; 16], attempt: u32) -> [u8; 16] {
+	if attempt == 0 {
+		random
+	} else {
+		(random, attempt).using_encoded(blake2_128)
+	}
+}
+
+/// Set the mixer to pick the stronger of both fighting stats in DNA bytes 8 to 10, see `mix_dna()`
+fn bias_to_stronger(mixer: [u8; 16], dna1: [u8; 16], dna2: [u8; 16]) -> [u8; 16] {
+	let mut mixer = mixer;
+	for i in 8..=10 {
+		mixer[i] = if dna1[i] % 16 >= dna2[i] % 16 { 0x00 } else { 0xff };
+	}
+	mixer
+}
+
 fn mix_dna(mixer: [u8; 16], dna1: [u8; 16], dna2: [u8; 16]) -> [u8; 16] {
     let mut res: [u8; 16] = [0u8; 16];
     for i in 0..mixer.len() {
@@ -1,12 +1,14 @@
 use super::*;
 use std::cell::RefCell;
+use std::collections::BTreeMap;
 use crate as kitties;
 use sp_core::H256;
-use frame_support::{parameter_types, assert_ok, assert_noop};
+use frame_support::{parameter_types, assert_ok, assert_noop, traits::{OnInitialize, OnRuntimeUpgrade}};
 use sp_runtime::{
 	traits::{BlakeTwo256, IdentityLookup}, testing::Header,
 };
 
+type Error = crate::Error<Test, DefaultInstance>;
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
 
@@ -20,6 +22,7 @@ frame_support::construct_runtime!(
 		System: frame_system::{Module, Call, Config, Storage, Event<T>},
 		Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
 		KittiesModule: kitties::{Module, Call, Storage, Event<T>},
+		Puppies: kitties::<Instance1>::{Module, Call, Storage, Event<T>},
 	}
 );
 
@@ -83,13 +86,160 @@ fn set_random(val: H256) {
     RANDOM_PAYLOAD.with(|v| *v.borrow_mut() = val)
 }
 
+// setup kitty deposit, none unless set
+thread_local! {
+    static KITTY_DEPOSIT: RefCell<u64> = RefCell::new(0);
+}
+
+pub struct KittyDeposit;
+
+impl Get<u64> for KittyDeposit {
+    fn get() -> u64 {
+        KITTY_DEPOSIT.with(|v| *v.borrow())
+    }
+}
+
+fn set_kitty_deposit(val: u64) {
+    KITTY_DEPOSIT.with(|v| *v.borrow_mut() = val)
+}
+
+// setup transfer hook, records ownership changes and vetoes those of a locked kitty
+thread_local! {
+    static LOCKED_KITTY: RefCell<Option<u32>> = RefCell::new(None);
+    static OWNERSHIP_CHANGES: RefCell<Vec<(Option<u64>, Option<u64>, u32)>> = RefCell::new(vec![]);
+}
 
+pub struct MockTransferHook;
+
+impl OnKittyTransfer<u64, u32> for MockTransferHook {
+    fn can_transfer(_from: Option<&u64>, _to: Option<&u64>, kitty_id: &u32) -> DispatchResult {
+        match LOCKED_KITTY.with(|v| *v.borrow()) {
+            Some(locked) if locked == *kitty_id => Err(DispatchError::Other("KittyLocked")),
+            _ => Ok(()),
+        }
+    }
+
+    fn on_transfer(from: Option<&u64>, to: Option<&u64>, kitty_id: &u32) {
+        OWNERSHIP_CHANGES.with(|v| v.borrow_mut().push((from.cloned(), to.cloned(), *kitty_id)))
+    }
+}
+
+fn set_locked_kitty(val: Option<u32>) {
+    LOCKED_KITTY.with(|v| *v.borrow_mut() = val)
+}
+
+fn ownership_changes() -> Vec<(Option<u64>, Option<u64>, u32)> {
+    OWNERSHIP_CHANGES.with(|v| v.borrow().clone())
+}
+
+// setup assets, a ledger of (asset_id, account) => balance
+thread_local! {
+    static ASSET_BALANCES: RefCell<BTreeMap<(u32, u64), u64>> = RefCell::new(BTreeMap::new());
+}
+
+pub struct MockAssets;
+
+impl FungibleAssets<u64> for MockAssets {
+    type AssetId = u32;
+    type Balance = u64;
+
+    fn transfer(asset_id: u32, source: &u64, dest: &u64, amount: u64) -> DispatchResult {
+        ASSET_BALANCES.with(|v| {
+            let mut balances = v.borrow_mut();
+            let source_balance = balances.get(&(asset_id, *source)).cloned().unwrap_or(0);
+            ensure!(source_balance >= amount, DispatchError::Other("InsufficientAssetBalance"));
+            balances.insert((asset_id, *source), source_balance - amount);
+            *balances.entry((asset_id, *dest)).or_insert(0) += amount;
+            Ok(())
+        })
+    }
+
+    fn transfer_weight() -> Weight {
+        0
+    }
+
+    fn create(_asset_id: u32, _owner: &u64, _min_balance: u64) -> DispatchResult {
+        Ok(())
+    }
+}
+
+fn set_asset_balance(asset_id: u32, who: u64, val: u64) {
+    ASSET_BALANCES.with(|v| v.borrow_mut().insert((asset_id, who), val));
+}
+
+fn asset_balance(asset_id: u32, who: u64) -> u64 {
+    ASSET_BALANCES.with(|v| v.borrow().get(&(asset_id, who)).cloned().unwrap_or(0))
+}
+
+parameter_types! {
+	pub const GestationPeriod: u64 = 10;
+	pub const StringLimit: u32 = 8;
+	pub const KeyLimit: u32 = 4;
+	pub const ValueLimit: u32 = 8;
+	pub const MaxAttributes: u32 = 2;
+	pub const AttributeDepositPerByte: u64 = 1;
+	pub const NameLimit: u32 = 8;
+	pub const NameDeposit: u64 = 5;
+	pub const UriLimit: u32 = 16;
+	pub const MaxDnaRetries: u32 = 3;
+	pub const KittiesModuleId: ModuleId = ModuleId(*b"py/kitty");
+	pub const MaxBracketSize: u32 = 4;
+	pub const MaxActiveTournaments: u32 = 2;
+	pub const MaxBatchSize: u32 = 3;
+}
 
 impl Config for Test {
 	type Event = Event;
 	type Currency = Balances;
 	type KittyIndex = u32;
 	type Randomness = MockRandom;
+	type GestationPeriod = GestationPeriod;
+	type KittyDeposit = KittyDeposit;
+	type OnTransfer = MockTransferHook;
+	type AssetId = u32;
+	type Assets = MockAssets;
+	type CollectionId = u32;
+	type StringLimit = StringLimit;
+	type KeyLimit = KeyLimit;
+	type ValueLimit = ValueLimit;
+	type MaxAttributes = MaxAttributes;
+	type AttributeDepositPerByte = AttributeDepositPerByte;
+	type NameLimit = NameLimit;
+	type NameDeposit = NameDeposit;
+	type UriLimit = UriLimit;
+	type MaxDnaRetries = MaxDnaRetries;
+	type TournamentOrigin = frame_system::EnsureRoot<u64>;
+	type MaxBracketSize = MaxBracketSize;
+	type MaxActiveTournaments = MaxActiveTournaments;
+	type ModuleId = KittiesModuleId;
+	type MaxBatchSize = MaxBatchSize;
+}
+
+impl Config<Instance1> for Test {
+	type Event = Event;
+	type Currency = Balances;
+	type KittyIndex = u32;
+	type Randomness = MockRandom;
+	type GestationPeriod = GestationPeriod;
+	type KittyDeposit = KittyDeposit;
+	type OnTransfer = ();
+	type AssetId = u32;
+	type Assets = MockAssets;
+	type CollectionId = u32;
+	type StringLimit = StringLimit;
+	type KeyLimit = KeyLimit;
+	type ValueLimit = ValueLimit;
+	type MaxAttributes = MaxAttributes;
+	type AttributeDepositPerByte = AttributeDepositPerByte;
+	type NameLimit = NameLimit;
+	type NameDeposit = NameDeposit;
+	type UriLimit = UriLimit;
+	type MaxDnaRetries = MaxDnaRetries;
+	type TournamentOrigin = frame_system::EnsureRoot<u64>;
+	type MaxBracketSize = MaxBracketSize;
+	type MaxActiveTournaments = MaxActiveTournaments;
+	type ModuleId = KittiesModuleId;
+	type MaxBatchSize = MaxBatchSize;
 }
 
 // Build genesis storage according to the mock runtime.
@@ -137,26 +287,77 @@ fn can_breed_test() {
         assert_ok!(KittiesModule::create(Origin::signed(100)));
         assert_eq!(KittiesModule::next_kitty_id(), 2);
 
-        assert_noop!(KittiesModule::breed(Origin::signed(100), 0, 11), Error::<Test>::KittyNotOwned);
-        assert_noop!(KittiesModule::breed(Origin::signed(100), 0, 0),  Error::<Test>::KittiesBredFromSameGenderCouple);
-        assert_noop!(KittiesModule::breed(Origin::signed(101), 0, 1),  Error::<Test>::KittyNotOwned);
+        assert_noop!(KittiesModule::breed(Origin::signed(100), 0, 11), Error::KittyNotOwned);
+        assert_noop!(KittiesModule::breed(Origin::signed(100), 0, 0),  Error::KittiesBredFromSameGenderCouple);
+        assert_noop!(KittiesModule::breed(Origin::signed(101), 0, 1),  Error::KittyNotOwned);
 
         assert_ok!(KittiesModule::breed(Origin::signed(100), 0, 1));
         assert_eq!(KittiesModule::next_kitty_id(), 3);
+        assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::KittyBred(100u64, 2u32, 0u32, 1u32, 11u64))));
+
+		// kitten not born until due block
+        assert_eq!(KittiesModule::kitties(100, 2), None);
+        assert_eq!(KittiesModule::births(11), vec![0]);
+        assert_noop!(KittiesModule::breed(Origin::signed(100), 0, 1), Error::KittyAlreadyPregnant);
 
-        // old way, with inbuilt randomness: let kitty = Kitty([59, 254, 219, 122, 245, 239, 191, 125, 255, 239, 247, 247, 251, 239, 247, 254]);
-		let kitty = Kitty([187, 254, 239, 222, 215, 167, 173, 111, 247, 254, 255, 189, 239, 186, 115, 123]);
+		System::set_block_number(11);
+		KittiesModule::on_initialize(11);
+
+		// old way, with inbuilt randomness: let kitty = Kitty([59, 254, 219, 122, 245, 239, 191, 125, 255, 239, 247, 247, 251, 239, 247, 254]);
+		let kitty = Kitty([19, 140, 14, 206, 214, 161, 172, 45, 22, 246, 254, 184, 170, 154, 99, 81]);
 
         assert_eq!(KittiesModule::kitties(100, 2), Some(kitty.clone()));
+        assert_eq!(KittiesModule::parents(2), Some((0, 1)));
+        assert_eq!(KittiesModule::pregnancies(0), None);
+        assert_eq!(KittiesModule::births(11), Vec::<u32>::new());
 
 		let momma = KittiesModule::kitties(100, 0).unwrap();
 		assert_eq!(momma.get_gender(), Gender::Female);
 		let poppa = KittiesModule::kitties(100, 1).unwrap();
 		assert_eq!(poppa.get_gender(), Gender::Male);
-        assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::KittyBred(100u64, 2u32, kitty, momma, poppa))));
+        assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::KittyBorn(100u64, 2u32, kitty))));
+
+		// momma can breed again after giving birth
+        assert_ok!(KittiesModule::breed(Origin::signed(100), 0, 1));
     });
 }
 
+#[test]
+fn breed_with_sire_test() {
+    new_test_ext().execute_with(|| {
+		let me_id = 100;
+		let stud_owner_id = 300;
+		assert_ok!(KittiesModule::create(Origin::signed(me_id)));  // female
+		assert_ok!(KittiesModule::create(Origin::signed(stud_owner_id)));  // male
+
+		assert_noop!(KittiesModule::offer_siring(Origin::signed(me_id), 0, 50, 5), Error::KittyNotMale);
+		assert_noop!(KittiesModule::offer_siring(Origin::signed(me_id), 1, 50, 5), Error::KittyNotOwned);
+		assert_noop!(KittiesModule::breed_with_sire(Origin::signed(me_id), 0, 1, 50), Error::SiringOfferNotFound);
+
+		assert_ok!(KittiesModule::offer_siring(Origin::signed(stud_owner_id), 1, 50, 5));
+		assert_eq!(last_event(), Some(Event::kitties(RawEvent::SiringOffered(stud_owner_id, 1, 50, 6))));
+
+		assert_noop!(KittiesModule::breed_with_sire(Origin::signed(me_id), 0, 1, 10), Error::KittyPriceTooLow);
+		assert_noop!(KittiesModule::breed_with_sire(Origin::signed(200), 0, 1, 50), Error::KittyNotOwned);
+
+		// fee goes to the sire's owner, sire stays put
+		assert_ok!(KittiesModule::breed_with_sire(Origin::signed(me_id), 0, 1, 50));
+		assert_eq!(last_event(), Some(Event::kitties(RawEvent::KittyBred(me_id, 2, 0, 1, 11))));
+		assert_eq!(Balances::free_balance(me_id), 50);
+		assert_eq!(Balances::free_balance(stud_owner_id), 350);
+		assert!(Kitties::<Test>::contains_key(stud_owner_id, 1));
+		assert_eq!(KittiesModule::pregnancies(0).map(|p| (p.owner, p.papa_id)), Some((me_id, 1)));
+
+		// offer expires
+		System::set_block_number(6);
+		assert_noop!(KittiesModule::breed_with_sire(Origin::signed(me_id), 0, 1, 50), Error::SiringOfferExpired);
+
+		assert_noop!(KittiesModule::cancel_siring(Origin::signed(me_id), 1), Error::KittyNotOwned);
+		assert_ok!(KittiesModule::cancel_siring(Origin::signed(stud_owner_id), 1));
+		assert_eq!(KittiesModule::siring_offers(1), None);
+	});
+}
+
 #[test]
 fn transfer_test() {
     new_test_ext().execute_with(|| {
@@ -167,7 +368,7 @@ fn transfer_test() {
 		let kitty1 = KittiesModule::kitties(me_id, 0).unwrap();
 
 		// invalid transfer of kitty not owned by myself
-		assert_noop!(KittiesModule::transfer(me.clone(), me_id, 10), Error::<Test>::KittyNotOwned);
+		assert_noop!(KittiesModule::transfer(me.clone(), me_id, 10), Error::KittyNotOwned);
 
 		// valid transfer to myself, expect no event
 		assert_ok!(KittiesModule::transfer(me.clone(), me_id, 0));
@@ -183,6 +384,157 @@ fn transfer_test() {
 	});
 }
 
+#[test]
+fn transfer_from_test() {
+    new_test_ext().execute_with(|| {
+		let me_id = 100;
+		let me = Origin::signed(me_id);
+		let spender_id = 200;
+		let operator_id = 300;
+		assert_ok!(KittiesModule::create(me.clone()));
+		assert_ok!(KittiesModule::create(me.clone()));
+
+		// not approved
+		assert_noop!(KittiesModule::transfer_from(Origin::signed(spender_id), me_id, spender_id, 0), Error::KittyNotApproved);
+		assert_noop!(KittiesModule::approve(Origin::signed(spender_id), 0, Some(spender_id)), Error::KittyNotOwned);
+
+		// per kitty approval, dropped after transfer
+		assert_ok!(KittiesModule::approve(me.clone(), 0, Some(spender_id)));
+		assert_eq!(last_event(), Some(Event::kitties(RawEvent::KittyApproved(me_id, Some(spender_id), 0))));
+		assert_noop!(KittiesModule::transfer_from(Origin::signed(spender_id), me_id, spender_id, 1), Error::KittyNotApproved);
+		assert_ok!(KittiesModule::transfer_from(Origin::signed(spender_id), me_id, spender_id, 0));
+		assert!(Kitties::<Test>::contains_key(spender_id, 0));
+		assert_eq!(KittiesModule::approvals(0), None);
+
+		// revoked approval
+		assert_ok!(KittiesModule::approve(me.clone(), 1, Some(spender_id)));
+		assert_ok!(KittiesModule::approve(me.clone(), 1, None));
+		assert_noop!(KittiesModule::transfer_from(Origin::signed(spender_id), me_id, spender_id, 1), Error::KittyNotApproved);
+
+		// operator for all kitties
+		assert_ok!(KittiesModule::set_approval_for_all(me.clone(), operator_id, true));
+		assert_eq!(last_event(), Some(Event::kitties(RawEvent::OperatorApproved(me_id, operator_id, true))));
+		assert_ok!(KittiesModule::transfer_from(Origin::signed(operator_id), me_id, spender_id, 1));
+		assert!(Kitties::<Test>::contains_key(spender_id, 1));
+		assert_eq!(last_event(), Some(Event::kitties(RawEvent::KittyTransfered(me_id, spender_id, 1, KittiesModule::kitties(spender_id, 1).unwrap()))));
+
+		// operator can't move kitties of other owners
+		assert_noop!(KittiesModule::transfer_from(Origin::signed(operator_id), spender_id, operator_id, 1), Error::KittyNotApproved);
+
+		assert_ok!(KittiesModule::set_approval_for_all(me.clone(), operator_id, false));
+		assert!(! KittiesModule::operators(me_id, operator_id));
+	});
+}
+
+#[test]
+fn offer_transfer_test() {
+    new_test_ext().execute_with(|| {
+		let me_id = 100;
+		let me = Origin::signed(me_id);
+		let friend_id = 200;
+		let friend = Origin::signed(friend_id);
+		assert_ok!(KittiesModule::create(me.clone()));
+
+		assert_noop!(KittiesModule::offer_transfer(friend.clone(), me_id, 0, 10), Error::KittyNotOwned);
+		assert_noop!(KittiesModule::offer_transfer(me.clone(), me_id, 0, 10), Error::CannotTransferToSelf);
+		assert_ok!(KittiesModule::offer_transfer(me.clone(), friend_id, 0, 10));
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::TransferOffered(me_id, friend_id, 0, 11))));
+		// nothing moves until accepted
+		assert!(Kitties::<Test>::contains_key(me_id, 0));
+		assert_noop!(KittiesModule::accept_transfer(Origin::signed(300), 0), Error::TransferOfferNotFound);
+
+		assert_ok!(KittiesModule::accept_transfer(friend.clone(), 0));
+		assert!(Kitties::<Test>::contains_key(friend_id, 0));
+		assert_eq!(KittiesModule::transfer_offers(0), None);
+
+		// declined by the recipient
+		assert_ok!(KittiesModule::offer_transfer(friend.clone(), me_id, 0, 10));
+		assert_noop!(KittiesModule::cancel_transfer(Origin::signed(300), 0), Error::TransferOfferNotFound);
+		assert_ok!(KittiesModule::cancel_transfer(me.clone(), 0));
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::TransferOfferCancelled(friend_id, me_id, 0))));
+		assert_noop!(KittiesModule::accept_transfer(me.clone(), 0), Error::TransferOfferNotFound);
+
+		// expired
+		assert_ok!(KittiesModule::offer_transfer(friend.clone(), me_id, 0, 10));
+		System::set_block_number(11);
+		assert_noop!(KittiesModule::accept_transfer(me.clone(), 0), Error::TransferOfferExpired);
+		KittiesModule::on_initialize(11);
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::TransferOfferExpired(friend_id, me_id, 0))));
+		assert_eq!(KittiesModule::transfer_offers(0), None);
+
+		// dropped once the kitty changes hands
+		assert_ok!(KittiesModule::offer_transfer(friend.clone(), me_id, 0, 10));
+		assert_ok!(KittiesModule::transfer(friend.clone(), 300, 0));
+		assert_eq!(KittiesModule::transfer_offers(0), None);
+		assert!(KittiesModule::transfer_offer_expiries(21).is_empty());
+
+		// replaced and withdrawn offers leave the expiry index
+		assert_ok!(KittiesModule::offer_transfer(Origin::signed(300), me_id, 0, 10));
+		assert_ok!(KittiesModule::offer_transfer(Origin::signed(300), me_id, 0, 20));
+		assert!(KittiesModule::transfer_offer_expiries(21).is_empty());
+		assert_eq!(KittiesModule::transfer_offer_expiries(31), vec![0]);
+		assert_ok!(KittiesModule::cancel_transfer(Origin::signed(300), 0));
+		assert!(KittiesModule::transfer_offer_expiries(31).is_empty());
+	});
+}
+
+#[test]
+fn kitty_registry_test() {
+    new_test_ext().execute_with(|| {
+		let me_id = 100;
+		let another_id = 200;
+		assert_ok!(KittiesModule::create(Origin::signed(me_id)));
+		let kitty = KittiesModule::kitties(me_id, 0);
+
+		assert_eq!(<KittiesModule as KittyRegistry<u64>>::owner_of(&0), Some(me_id));
+		assert_eq!(<KittiesModule as KittyRegistry<u64>>::kitty(&0), kitty);
+		assert_eq!(<KittiesModule as KittyRegistry<u64>>::owner_of(&1), None);
+		assert_eq!(<KittiesModule as KittyRegistry<u64>>::kitty(&1), None);
+
+		assert_ok!(<KittiesModule as KittyRegistry<u64>>::transfer(&0, &another_id));
+		assert_eq!(<KittiesModule as KittyRegistry<u64>>::owner_of(&0), Some(another_id));
+		assert_eq!(KittiesModule::kitties(another_id, 0), kitty);
+		assert_noop!(<KittiesModule as KittyRegistry<u64>>::transfer(&1, &another_id), Error::KittyNotOwned);
+
+		assert_ok!(KittiesModule::burn(Origin::signed(another_id), 0));
+		assert_eq!(<KittiesModule as KittyRegistry<u64>>::owner_of(&0), None);
+	});
+}
+
+#[test]
+fn on_transfer_hook_test() {
+    new_test_ext().execute_with(|| {
+		let me_id = 100;
+		let me = Origin::signed(me_id);
+		let rich_buyer = 300;
+		assert_ok!(KittiesModule::create(me.clone()));
+		assert_ok!(KittiesModule::transfer(me.clone(), 200, 0));
+		assert_ok!(KittiesModule::transfer(Origin::signed(200), me_id, 0));
+		assert_ok!(KittiesModule::set_price(me.clone(), 0, Some(10)));
+		assert_ok!(KittiesModule::buy(Origin::signed(rich_buyer), 0, 10));
+		assert_ok!(KittiesModule::burn(Origin::signed(rich_buyer), 0));
+		assert_eq!(ownership_changes(), vec![
+			(None, Some(me_id), 0),
+			(Some(me_id), Some(200), 0),
+			(Some(200), Some(me_id), 0),
+			(Some(me_id), Some(rich_buyer), 0),
+			(Some(rich_buyer), None, 0),
+		]);
+
+		// locked kitty can't change hands
+		assert_ok!(KittiesModule::create(me.clone()));
+		set_locked_kitty(Some(1));
+		assert_noop!(KittiesModule::transfer(me.clone(), 200, 1), DispatchError::Other("KittyLocked"));
+		assert_ok!(KittiesModule::set_price(me.clone(), 1, Some(10)));
+		assert_noop!(KittiesModule::buy(Origin::signed(rich_buyer), 1, 10), DispatchError::Other("KittyLocked"));
+		assert_noop!(KittiesModule::burn(me.clone(), 1), DispatchError::Other("KittyLocked"));
+
+		// nor can it be minted
+		set_locked_kitty(Some(2));
+		assert_noop!(KittiesModule::create(me.clone()), DispatchError::Other("KittyLocked"));
+	});
+}
+
 #[test]
 fn set_price_test() {
     new_test_ext().execute_with(|| {
@@ -201,7 +553,7 @@ fn set_price_test() {
 		assert_eq!(Prices::<Test>::get(0), Some(100_u64));
 
 		// set price on someone else's kitty
-		assert_noop!(KittiesModule::set_price(me.clone(), another_id, None), Error::<Test>::KittyNotOwned);
+		assert_noop!(KittiesModule::set_price(me.clone(), another_id, None), Error::KittyNotOwned);
 		assert_eq!(Prices::<Test>::get(0), Some(100_u64));
 	});
 }
@@ -217,26 +569,29 @@ fn set_buy() {
 		assert_ok!(KittiesModule::create(me.clone()));
 
 		// try to buy unpriced kitty
-		assert_noop!(KittiesModule::buy(me.clone(), poor_buyer, 0, 250), Error::<Test>::KittyNotForSale);
+		assert_noop!(KittiesModule::buy(Origin::signed(poor_buyer), 0, 250), Error::KittyNotForSale);
+
+		// buyers sign their own purchases, owners can't buy from themselves
+		assert_noop!(KittiesModule::buy(me.clone(), 0, 250), Error::CannotTransferToSelf);
 
 		// try to buy non existant kitty
-		assert_noop!(KittiesModule::buy(me.clone(), poor_buyer, 10, 250), Error::<Test>::KittyNotOwned);
+		assert_noop!(KittiesModule::buy(Origin::signed(poor_buyer), 10, 250), Error::KittyNotOwned);
 
 		// try to buy below price
 		assert_ok!(KittiesModule::set_price(me.clone(), 0, Some(200)));
-		assert_noop!(KittiesModule::buy(me.clone(), poor_buyer, 0, 10), Error::<Test>::KittyPriceTooLow);
+		assert_noop!(KittiesModule::buy(Origin::signed(poor_buyer), 0, 10), Error::KittyPriceTooLow);
 
 		// fail to buy due to depleting balance to 0
 		assert_ok!(KittiesModule::set_price(me.clone(), 0, Some(200)));
-		assert_noop!(KittiesModule::buy(me.clone(), poor_buyer, 0, 1000), pallet_balances::Error::<Test, _>::KeepAlive);
+		assert_noop!(KittiesModule::buy(Origin::signed(poor_buyer), 0, 1000), pallet_balances::Error::<Test, _>::KeepAlive);
 
 		// fail to buy due depleting the balance < 0
 		assert_ok!(KittiesModule::set_price(me.clone(), 0, Some(250)));
 		assert_eq!(Prices::<Test>::get(0), Some(250));
-		assert_noop!(KittiesModule::buy(me.clone(), poor_buyer, 0, 1000), pallet_balances::Error::<Test, _>::InsufficientBalance);
+		assert_noop!(KittiesModule::buy(Origin::signed(poor_buyer), 0, 1000), pallet_balances::Error::<Test, _>::InsufficientBalance);
 
 		// buy ok! and not be able to buy again due to kitty being unpriced post transfer
-		assert_ok!(KittiesModule::buy(me.clone(), rich_buyer, 0, 1000));
+		assert_ok!(KittiesModule::buy(Origin::signed(rich_buyer), 0, 1000));
 		assert_eq!(last_event(), Some(Event::kitties(RawEvent::KittyBought(100, rich_buyer, 0, 250))));
 		assert_eq!(Prices::<Test>::get(0), None);
 		assert!(! Kitties::<Test>::contains_key(me_id, 0));
@@ -248,6 +603,843 @@ fn set_buy() {
 	});
 }
 
+#[test]
+fn buy_in_asset_test() {
+    new_test_ext().execute_with(|| {
+		let me_id = 100;
+		let me = Origin::signed(me_id);
+		let poor_buyer = 200;
+		let rich_buyer = 300;
+		let asset_id = 7;
+		set_asset_balance(asset_id, poor_buyer, 10);
+		set_asset_balance(asset_id, rich_buyer, 100);
+
+		assert_ok!(KittiesModule::create(me.clone()));
+		assert_noop!(KittiesModule::set_price_in_asset(me.clone(), 0, asset_id, 50), Error::AssetNotAccepted);
+
+		assert_noop!(KittiesModule::set_accepted_assets(me.clone(), vec![asset_id]), DispatchError::BadOrigin);
+		assert_ok!(KittiesModule::set_accepted_assets(Origin::root(), vec![asset_id]));
+		assert_noop!(KittiesModule::set_price_in_asset(Origin::signed(poor_buyer), 0, asset_id, 50), Error::KittyNotOwned);
+		assert_ok!(KittiesModule::set_price_in_asset(me.clone(), 0, asset_id, 50));
+		assert_eq!(last_event(), Some(Event::kitties(RawEvent::KittyPriceSetInAsset(me_id, 0, asset_id, 50))));
+		assert_eq!(KittiesModule::prices(0), Some(50));
+		assert_eq!(KittiesModule::price_assets(0), Some(asset_id));
+
+		assert_noop!(KittiesModule::buy(Origin::signed(poor_buyer), 0, 50), DispatchError::Other("InsufficientAssetBalance"));
+
+		// settled in the asset, native balances untouched
+		assert_ok!(KittiesModule::buy(Origin::signed(rich_buyer), 0, 50));
+		assert!(Kitties::<Test>::contains_key(rich_buyer, 0));
+		assert_eq!(asset_balance(asset_id, me_id), 50);
+		assert_eq!(asset_balance(asset_id, rich_buyer), 50);
+		assert_eq!(Balances::free_balance(me_id), 100);
+		assert_eq!(Balances::free_balance(rich_buyer), 300);
+		assert_eq!(KittiesModule::prices(0), None);
+		assert_eq!(KittiesModule::price_assets(0), None);
+
+		// re-pricing in native currency drops the asset
+		assert_ok!(KittiesModule::set_price_in_asset(Origin::signed(rich_buyer), 0, asset_id, 50));
+		assert_ok!(KittiesModule::set_price(Origin::signed(rich_buyer), 0, Some(60)));
+		assert_eq!(KittiesModule::price_assets(0), None);
+	});
+}
+
+#[test]
+fn collection_test() {
+    new_test_ext().execute_with(|| {
+		let creator_id = 100;
+		let creator = Origin::signed(creator_id);
+		let seller_id = 200;
+		let buyer_id = 300;
+
+		assert_noop!(KittiesModule::create_collection(creator.clone(), b"Too long name".to_vec(), None, Permill::zero(), true), Error::CollectionNameTooLong);
+		assert_ok!(KittiesModule::create_collection(creator.clone(), b"Cats".to_vec(), Some(2), Permill::from_percent(10), true));
+		assert_eq!(last_event(), Some(Event::kitties(RawEvent::CollectionCreated(creator_id, 0))));
+		assert_eq!(KittiesModule::next_collection_id(), 1);
+
+		// only the collection owner mints, up to max supply
+		assert_noop!(KittiesModule::mint_into(Origin::signed(seller_id), 0, seller_id, false), Error::NotCollectionOwner);
+		assert_noop!(KittiesModule::mint_into(creator.clone(), 1, seller_id, false), Error::CollectionNotFound);
+		assert_ok!(KittiesModule::mint_into(creator.clone(), 0, seller_id, false));
+		assert_eq!(last_event(), Some(Event::kitties(RawEvent::CollectionKittyMinted(0, 0, 0))));
+		assert_ok!(KittiesModule::mint_into(creator.clone(), 0, buyer_id, false));
+		assert_noop!(KittiesModule::mint_into(creator.clone(), 0, buyer_id, false), Error::CollectionSupplyExhausted);
+		assert!(Kitties::<Test>::contains_key(seller_id, 0));
+		assert_eq!(KittiesModule::collection_kitties(0, 1), Some(1));
+		assert_eq!(KittiesModule::kitty_collection(1), Some((0, 1)));
+		assert_eq!(KittiesModule::collections(0).map(|c| c.minted), Some(2));
+
+		// kitties are addressed by collection id and index within the collection
+		assert_noop!(KittiesModule::transfer_in_collection(Origin::signed(seller_id), buyer_id, 0, 2), Error::CollectionKittyNotFound);
+		assert_noop!(KittiesModule::set_price_in_collection(Origin::signed(seller_id), 0, 1, Some(100)), Error::KittyNotOwned);
+		assert_ok!(KittiesModule::set_price_in_collection(Origin::signed(seller_id), 0, 0, Some(50)));
+		assert_eq!(KittiesModule::prices(0), Some(50));
+
+		// sale pays royalty to the collection owner
+		assert_ok!(KittiesModule::set_price(Origin::signed(seller_id), 0, Some(100)));
+		assert_ok!(KittiesModule::buy(Origin::signed(buyer_id), 0, 100));
+		assert_eq!(Balances::free_balance(creator_id), 110);
+		assert_eq!(Balances::free_balance(seller_id), 290);
+		assert_eq!(Balances::free_balance(buyer_id), 200);
+		assert_ok!(KittiesModule::transfer_in_collection(Origin::signed(buyer_id), seller_id, 0, 0));
+		assert!(Kitties::<Test>::contains_key(seller_id, 0));
+
+		// non transferable collection
+		assert_noop!(KittiesModule::set_collection_config(Origin::signed(buyer_id), 0, Permill::zero(), false), Error::NotCollectionOwner);
+		assert_ok!(KittiesModule::set_collection_config(creator.clone(), 0, Permill::zero(), false));
+		assert_eq!(last_event(), Some(Event::kitties(RawEvent::CollectionConfigSet(0, Permill::zero(), false))));
+		assert_noop!(KittiesModule::transfer(Origin::signed(buyer_id), seller_id, 1), Error::CollectionNotTransferable);
+		assert_noop!(KittiesModule::transfer_in_collection(Origin::signed(buyer_id), seller_id, 0, 1), Error::CollectionNotTransferable);
+
+		// burning is still allowed
+		assert_ok!(KittiesModule::burn_in_collection(Origin::signed(buyer_id), 0, 1));
+		assert_eq!(KittiesModule::collection_kitties(0, 1), None);
+		assert_eq!(KittiesModule::kitty_collection(1), None);
+	});
+}
+
+#[test]
+fn attributes_test() {
+    new_test_ext().execute_with(|| {
+		let me_id = 100;
+		let me = Origin::signed(me_id);
+		assert_ok!(KittiesModule::create(me.clone()));
+
+		assert_noop!(KittiesModule::set_attribute(Origin::signed(200), 0, b"hat".to_vec(), b"red".to_vec()), Error::KittyNotOwned);
+		assert_noop!(KittiesModule::set_attribute(me.clone(), 0, b"boots".to_vec(), b"red".to_vec()), Error::AttributeKeyTooLong);
+		assert_noop!(KittiesModule::set_attribute(me.clone(), 0, b"hat".to_vec(), b"red velvet".to_vec()), Error::AttributeValueTooLong);
+
+		// deposit per byte of key and value
+		assert_ok!(KittiesModule::set_attribute(me.clone(), 0, b"hat".to_vec(), b"red".to_vec()));
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::AttributeSet(0, b"hat".to_vec(), b"red".to_vec(), 6))));
+		assert_eq!(Balances::reserved_balance(me_id), 6);
+		assert_ok!(KittiesModule::set_attribute(me.clone(), 0, b"hat".to_vec(), b"blue".to_vec()));
+		assert_eq!(Balances::reserved_balance(me_id), 7);
+		assert_ok!(KittiesModule::set_attribute(me.clone(), 0, b"xp".to_vec(), b"9".to_vec()));
+		assert_eq!(Balances::reserved_balance(me_id), 10);
+		assert_eq!(KittiesModule::attributes(0, b"hat".to_vec()).map(|a| a.value), Some(b"blue".to_vec()));
+		assert_noop!(KittiesModule::set_attribute(me.clone(), 0, b"eyes".to_vec(), b"red".to_vec()), Error::TooManyAttributes);
+
+		let details = KittiesModule::kitty_details(0).unwrap();
+		assert_eq!(details.owner, me_id);
+		assert_eq!(details.dna, KittiesModule::kitties(me_id, 0).unwrap().0);
+		let mut attributes = details.attributes;
+		attributes.sort();
+		assert_eq!(attributes, vec![(b"hat".to_vec(), b"blue".to_vec()), (b"xp".to_vec(), b"9".to_vec())]);
+		assert_eq!(KittiesModule::kitty_details(1), None);
+
+		assert_noop!(KittiesModule::clear_attribute(me.clone(), 0, b"eyes".to_vec()), Error::AttributeNotFound);
+		assert_ok!(KittiesModule::clear_attribute(me.clone(), 0, b"hat".to_vec()));
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::AttributeCleared(0, b"hat".to_vec()))));
+		assert_eq!(Balances::reserved_balance(me_id), 3);
+
+		// burning refunds the remaining attribute deposits
+		assert_ok!(KittiesModule::burn(me.clone(), 0));
+		assert_eq!(KittiesModule::attributes(0, b"xp".to_vec()), None);
+		assert_eq!(Balances::reserved_balance(me_id), 0);
+		assert_eq!(Balances::free_balance(me_id), 100);
+	});
+}
+
+#[test]
+fn names_test() {
+    new_test_ext().execute_with(|| {
+		let me_id = 100;
+		let me = Origin::signed(me_id);
+		assert_ok!(KittiesModule::create(me.clone()));
+		assert_ok!(KittiesModule::create(Origin::signed(200)));
+
+		assert_noop!(KittiesModule::set_name(Origin::signed(200), 0, b"tom".to_vec()), Error::KittyNotOwned);
+		assert_noop!(KittiesModule::set_name(me.clone(), 0, vec![]), Error::NameEmpty);
+		assert_noop!(KittiesModule::set_name(me.clone(), 0, b"tom the cat".to_vec()), Error::NameTooLong);
+
+		assert_ok!(KittiesModule::set_name(me.clone(), 0, b"tom".to_vec()));
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::KittyNamed(me_id, 0, b"tom".to_vec()))));
+		assert_eq!(KittiesModule::named_kitty(b"tom".to_vec()), Some(0));
+		assert_eq!(Balances::reserved_balance(me_id), 5);
+		assert_eq!(KittiesModule::kitty_details(0).unwrap().name, Some(b"tom".to_vec()));
+
+		// names are unique
+		assert_noop!(KittiesModule::set_name(Origin::signed(200), 1, b"tom".to_vec()), Error::NameTaken);
+
+		// renaming frees the old name, deposit is kept reserved once
+		assert_ok!(KittiesModule::set_name(me.clone(), 0, b"felix".to_vec()));
+		assert_eq!(KittiesModule::named_kitty(b"tom".to_vec()), None);
+		assert_eq!(Balances::reserved_balance(me_id), 5);
+		assert_ok!(KittiesModule::set_name(Origin::signed(200), 1, b"tom".to_vec()));
+
+		assert_ok!(KittiesModule::clear_name(Origin::signed(200), 1));
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::KittyNameCleared(200, 1, b"tom".to_vec()))));
+		assert_eq!(Balances::reserved_balance(200), 0);
+		assert_noop!(KittiesModule::clear_name(Origin::signed(200), 1), Error::NameNotFound);
+
+		// burning releases the name
+		assert_ok!(KittiesModule::burn(me.clone(), 0));
+		assert_eq!(KittiesModule::named_kitty(b"felix".to_vec()), None);
+		assert_eq!(KittiesModule::names(0), None);
+		assert_eq!(Balances::reserved_balance(me_id), 0);
+		assert_ok!(KittiesModule::set_name(Origin::signed(200), 1, b"felix".to_vec()));
+	});
+}
+
+#[test]
+fn metadata_test() {
+    new_test_ext().execute_with(|| {
+		let me_id = 100;
+		let me = Origin::signed(me_id);
+		let hash = H256::repeat_byte(1);
+		assert_ok!(KittiesModule::create_collection(me.clone(), b"cats".to_vec(), None, Permill::zero(), true));
+		assert_ok!(KittiesModule::mint_into(me.clone(), 0, me_id, false));
+		assert_ok!(KittiesModule::create(me.clone()));
+
+		assert_noop!(KittiesModule::set_metadata(Origin::signed(200), 0, b"ipfs://a".to_vec(), hash), Error::KittyNotOwned);
+		assert_noop!(KittiesModule::set_metadata(me.clone(), 0, b"ipfs://way-too-long".to_vec(), hash), Error::UriTooLong);
+		assert_noop!(KittiesModule::freeze_metadata(me.clone(), 0), Error::MetadataNotFound);
+		assert_noop!(KittiesModule::set_collection_metadata(Origin::signed(200), 0, b"ipfs://c".to_vec(), hash), Error::NotCollectionOwner);
+
+		// collection metadata applies to kitties without their own
+		assert_ok!(KittiesModule::set_collection_metadata(me.clone(), 0, b"ipfs://c".to_vec(), hash));
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::CollectionMetadataSet(0, b"ipfs://c".to_vec(), hash))));
+		assert_eq!(KittiesModule::metadata_of(0).map(|m| m.uri), Some(b"ipfs://c".to_vec()));
+		assert_eq!(KittiesModule::metadata_of(1), None);
+		assert_ok!(KittiesModule::freeze_collection_metadata(me.clone(), 0));
+		assert_noop!(KittiesModule::set_collection_metadata(me.clone(), 0, b"ipfs://d".to_vec(), hash), Error::MetadataIsFrozen);
+
+		assert_ok!(KittiesModule::set_metadata(me.clone(), 0, b"ipfs://a".to_vec(), hash));
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::MetadataSet(0, b"ipfs://a".to_vec(), hash))));
+		assert_eq!(KittiesModule::metadata_of(0).map(|m| m.uri), Some(b"ipfs://a".to_vec()));
+
+		assert_ok!(KittiesModule::freeze_metadata(me.clone(), 0));
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::MetadataFrozen(0))));
+		assert_noop!(KittiesModule::set_metadata(me.clone(), 0, b"ipfs://b".to_vec(), H256::repeat_byte(2)), Error::MetadataIsFrozen);
+		assert_noop!(KittiesModule::freeze_metadata(me.clone(), 0), Error::MetadataIsFrozen);
+
+		assert_ok!(KittiesModule::burn(me.clone(), 0));
+		assert_eq!(KittiesModule::kitty_metadata(0), None);
+	});
+}
+
+#[test]
+fn unique_dna_test() {
+    new_test_ext().execute_with(|| {
+		let me_id = 100;
+		let me = Origin::signed(me_id);
+
+		// same sender, seed and extrinsic index, second kitty gets rerolled DNA
+		assert_ok!(KittiesModule::create(me.clone()));
+		assert_ok!(KittiesModule::create(me.clone()));
+		let dna0 = KittiesModule::kitties(me_id, 0).unwrap().0;
+		let dna1 = KittiesModule::kitties(me_id, 1).unwrap().0;
+		assert_eq!(dna0, KittiesModule::random_value(&me_id));
+		assert_eq!(dna1, reroll(dna0, 1));
+		assert_eq!(KittiesModule::dna_kitty(dna0), Some(0));
+		assert_eq!(KittiesModule::dna_kitty(dna1), Some(1));
+
+		// all retries clash
+		assert_ok!(KittiesModule::create(me.clone()));
+		assert_ok!(KittiesModule::create(me.clone()));
+		assert_noop!(KittiesModule::create(me.clone()), Error::DuplicateDna);
+
+		// burning frees the DNA
+		assert_ok!(KittiesModule::burn(me.clone(), 0));
+		assert_eq!(KittiesModule::dna_kitty(dna0), None);
+		assert_ok!(KittiesModule::create(me.clone()));
+		assert_eq!(KittiesModule::kitties(me_id, 4).unwrap().0, dna0);
+	});
+}
+
+#[test]
+fn rarity_test() {
+    new_test_ext().execute_with(|| {
+		let me_id = 100;
+		// fur colour, pattern, eye colour, eye shape, tail and whiskers in bytes 1 to 6
+		let common = [0, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+		let rare = [0, 2, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0];
+		assert_ok!(KittiesModule::mint(&me_id, &me_id, common));
+		assert_eq!(KittiesModule::rarity(0), Some(600));
+
+		assert_ok!(KittiesModule::mint(&me_id, &me_id, [1; 16]));
+		assert_ok!(KittiesModule::mint(&me_id, &me_id, rare));
+		assert_eq!(KittiesModule::kitty_count(), 3);
+		assert_eq!(KittiesModule::trait_count(Gene::FurColour, 1), 2);
+		assert_eq!(KittiesModule::trait_count(Gene::FurColour, 2), 1);
+		assert_eq!(KittiesModule::trait_count(Gene::Whiskers, 1), 3);
+		// fur colour is unique, other traits shared by all three
+		assert_eq!(KittiesModule::rarity(2), Some(300 + 5 * 100));
+		assert_eq!(KittiesModule::rarity(0), Some(150 + 5 * 100));
+		assert_eq!(KittiesModule::rarity(3), None);
+
+		assert_ok!(KittiesModule::burn(Origin::signed(me_id), 1));
+		assert_eq!(KittiesModule::kitty_count(), 2);
+		assert_eq!(KittiesModule::trait_count(Gene::FurColour, 1), 1);
+		assert_eq!(KittiesModule::rarity(0), Some(200 + 5 * 100));
+	});
+}
+
+#[test]
+fn battle_test() {
+    new_test_ext().execute_with(|| {
+		let me_id = 100;
+		let me = Origin::signed(me_id);
+		let rival_id = 200;
+		let rival = Origin::signed(rival_id);
+		// attack, defence and speed in bytes 8 to 10
+		assert_ok!(KittiesModule::mint(&me_id, &me_id, [0, 0, 0, 0, 0, 0, 0, 0, 15, 15, 15, 0, 0, 0, 0, 0]));
+		assert_ok!(KittiesModule::mint(&rival_id, &rival_id, [0; 16]));
+
+		assert_noop!(KittiesModule::challenge(rival.clone(), 0, 1, 50), Error::KittyNotOwned);
+		assert_noop!(KittiesModule::challenge(me.clone(), 0, 0, 50), Error::CannotFightSelf);
+		assert_noop!(KittiesModule::challenge(me.clone(), 0, 2, 50), Error::KittyNotOwned);
+
+		assert_ok!(KittiesModule::challenge(me.clone(), 0, 1, 50));
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::BattleChallenged(me_id, 0, 1, 50))));
+		assert_eq!(Balances::reserved_balance(me_id), 50);
+		assert_noop!(KittiesModule::challenge(me.clone(), 0, 1, 50), Error::ChallengeExists);
+		assert_noop!(KittiesModule::accept_challenge(me.clone(), 1, 0), Error::KittyNotOwned);
+		assert_noop!(KittiesModule::accept_challenge(rival.clone(), 1, 1), Error::ChallengeNotFound);
+
+		// the stronger challenger wins, taking both wagers
+		assert_ok!(KittiesModule::accept_challenge(rival.clone(), 1, 0));
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::WagerWon(me_id, rival_id, 50))));
+		let outcome = battle::fight(
+			Stats::from_dna(&KittiesModule::kitties(me_id, 0).unwrap().0),
+			Stats::from_dna(&[0; 16]),
+			KittiesModule::random_value(&rival_id),
+		);
+		assert!(outcome.challenger_wins);
+		assert!(System::events().iter().any(|r| r.event == Event::kitties(crate::Event::<Test>::BattleFought(0, 1, 0, outcome.strikes.clone()))));
+		assert_eq!(KittiesModule::progress(0).experience, battle::WIN_EXPERIENCE);
+		assert_eq!(KittiesModule::progress(1).experience, 0);
+		assert_eq!(KittiesModule::challenges(0), None);
+		assert_eq!(Balances::free_balance(me_id), 150);
+		assert_eq!(Balances::free_balance(rival_id), 150);
+		assert_eq!(Balances::reserved_balance(rival_id), 0);
+
+		// cancelling and burning refund the wager
+		assert_ok!(KittiesModule::challenge(rival.clone(), 1, 0, 20));
+		assert_noop!(KittiesModule::cancel_challenge(me.clone(), 1), Error::KittyNotOwned);
+		assert_ok!(KittiesModule::cancel_challenge(rival.clone(), 1));
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::BattleChallengeCancelled(rival_id, 1))));
+		assert_ok!(KittiesModule::challenge(rival.clone(), 1, 0, 20));
+		assert_ok!(KittiesModule::burn(rival.clone(), 1));
+		assert_eq!(Balances::reserved_balance(rival_id), 0);
+
+		// no fighting own kitties, even once the opponent changed hands since the challenge
+		assert_ok!(KittiesModule::mint(&300, &300, [3; 16]));
+		assert_ok!(KittiesModule::challenge(me.clone(), 0, 2, 10));
+		assert_ok!(KittiesModule::transfer(Origin::signed(300), me_id, 2));
+		assert_noop!(KittiesModule::challenge(me.clone(), 2, 0, 10), Error::CannotFightSelf);
+		assert_noop!(KittiesModule::accept_challenge(me.clone(), 2, 0), Error::CannotFightSelf);
+
+		// transferring the challenger refunds the wager
+		assert_ok!(KittiesModule::transfer(me.clone(), 300, 0));
+		assert_eq!(KittiesModule::challenges(0), None);
+		assert_eq!(Balances::reserved_balance(me_id), 0);
+	});
+}
+
+#[test]
+fn tournament_test() {
+    new_test_ext().execute_with(|| {
+		// weak, medium and strong kitties, attack, defence and speed in bytes 8 to 10
+		assert_ok!(KittiesModule::mint(&100, &100, [0; 16]));
+		assert_ok!(KittiesModule::mint(&200, &200, [0, 0, 0, 0, 0, 0, 0, 0, 7, 7, 7, 0, 0, 0, 0, 0]));
+		assert_ok!(KittiesModule::mint(&300, &300, [0, 0, 0, 0, 0, 0, 0, 0, 15, 15, 15, 0, 0, 0, 0, 0]));
+
+		assert_noop!(KittiesModule::create_tournament(Origin::signed(100), 10, 5, 4), DispatchError::BadOrigin);
+		assert_noop!(KittiesModule::create_tournament(Origin::root(), 10, 5, 3), Error::InvalidBracketSize);
+		assert_noop!(KittiesModule::create_tournament(Origin::root(), 10, 5, 8), Error::InvalidBracketSize);
+		assert_noop!(KittiesModule::create_tournament(Origin::root(), 10, 1, 4), Error::InvalidDeadline);
+		assert_ok!(KittiesModule::create_tournament(Origin::root(), 10, 5, 4));
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::TournamentCreated(0, 10, 5, 4))));
+		assert_eq!(KittiesModule::active_tournaments(), vec![0]);
+
+		assert_noop!(KittiesModule::enter_tournament(Origin::signed(100), 1, 0), Error::TournamentNotFound);
+		assert_noop!(KittiesModule::enter_tournament(Origin::signed(200), 0, 0), Error::KittyNotOwned);
+		assert_ok!(KittiesModule::enter_tournament(Origin::signed(100), 0, 0));
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::TournamentEntered(100, 0, 0))));
+		assert_noop!(KittiesModule::enter_tournament(Origin::signed(100), 0, 0), Error::AlreadyEntered);
+		assert_ok!(KittiesModule::enter_tournament(Origin::signed(200), 0, 1));
+		assert_ok!(KittiesModule::enter_tournament(Origin::signed(300), 0, 2));
+		assert_eq!(Balances::free_balance(KittiesModule::tournament_account(0)), 30);
+
+		// registration still open
+		KittiesModule::on_initialize(4);
+		assert_eq!(KittiesModule::tournaments(0).map(|t| t.status), Some(TournamentStatus::Registration));
+
+		// first round at the deadline, the strong kitty gets a bye
+		System::set_block_number(5);
+		assert_noop!(KittiesModule::enter_tournament(Origin::signed(300), 0, 2), Error::TournamentRegistrationClosed);
+		KittiesModule::on_initialize(5);
+		assert_eq!(KittiesModule::tournament_matches(0, 0), vec![(0, 1, 1)]);
+		let tournament = KittiesModule::tournaments(0).unwrap();
+		assert_eq!(tournament.status, TournamentStatus::InProgress);
+		assert_eq!(tournament.remaining, vec![1, 2]);
+
+		// final
+		System::set_block_number(6);
+		KittiesModule::on_initialize(6);
+		assert_eq!(KittiesModule::tournament_matches(0, 1), vec![(1, 2, 2)]);
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::TournamentWon(0, 2, 300, 30))));
+		let tournament = KittiesModule::tournaments(0).unwrap();
+		assert_eq!(tournament.status, TournamentStatus::Finished);
+		assert_eq!(tournament.winner, Some(2));
+		assert_eq!(KittiesModule::progress(1).experience, battle::WIN_EXPERIENCE);
+		assert_eq!(KittiesModule::progress(2).experience, battle::WIN_EXPERIENCE);
+		assert_eq!(Balances::free_balance(300), 320);
+		assert_eq!(Balances::free_balance(100), 90);
+		assert!(KittiesModule::active_tournaments().is_empty());
+
+		// a single entrant gets refunded
+		assert_ok!(KittiesModule::create_tournament(Origin::root(), 10, 10, 2));
+		assert_ok!(KittiesModule::enter_tournament(Origin::signed(100), 1, 0));
+		assert_eq!(Balances::free_balance(100), 80);
+		KittiesModule::on_initialize(10);
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::TournamentCancelled(1))));
+		assert_eq!(KittiesModule::tournaments(1).map(|t| t.status), Some(TournamentStatus::Cancelled));
+		assert_eq!(Balances::free_balance(100), 90);
+
+		// active tournaments are capped
+		assert_ok!(KittiesModule::create_tournament(Origin::root(), 0, 20, 2));
+		assert_ok!(KittiesModule::create_tournament(Origin::root(), 0, 20, 2));
+		assert_noop!(KittiesModule::create_tournament(Origin::root(), 0, 20, 2), Error::TooManyTournaments);
+	});
+}
+
+#[test]
+fn evolution_test() {
+    new_test_ext().execute_with(|| {
+		let me_id = 100;
+		assert_ok!(KittiesModule::mint(&me_id, &me_id, [0; 16]));
+
+		KittiesModule::gain_experience(0, 19);
+		assert_eq!(KittiesModule::progress(0), KittyProgress { experience: 19, level: 0 });
+		assert_eq!(KittiesModule::kitties(me_id, 0), Some(Kitty([0; 16])));
+
+		// first level raises attack
+		KittiesModule::gain_experience(0, 1);
+		let evolved = Kitty([0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 1, 0, 0, 0, 0]);
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::KittyEvolved(me_id, 0, 1, evolved.clone()))));
+		assert_eq!(KittiesModule::kitties(me_id, 0), Some(evolved.clone()));
+		assert_eq!(KittiesModule::dna_kitty(evolved.0), Some(0));
+		assert_eq!(KittiesModule::dna_kitty([0; 16]), None);
+
+		// evolves through every level reached
+		KittiesModule::gain_experience(0, 80);
+		assert_eq!(KittiesModule::progress(0), KittyProgress { experience: 100, level: 3 });
+		assert_eq!(KittiesModule::kitties(me_id, 0), Some(Kitty([0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 3, 0, 0, 0, 0])));
+		assert_eq!(Stats::from_dna(&KittiesModule::kitties(me_id, 0).unwrap().0), Stats { attack: 2, defence: 2, speed: 2 });
+
+		// breeding earns experience
+		assert_ok!(KittiesModule::mint(&me_id, &me_id, [1; 16]));
+		assert_ok!(KittiesModule::breed(Origin::signed(me_id), 1, 0));
+		assert_eq!(KittiesModule::progress(1).experience, evolution::BREED_EXPERIENCE);
+		assert_eq!(KittiesModule::progress(0).experience, 100 + evolution::BREED_EXPERIENCE);
+	});
+}
+
+#[test]
+fn migrate_to_v2_test() {
+    new_test_ext().execute_with(|| {
+		// kitties as stored before `Releases::V2`, unindexed
+		Kitties::<Test>::insert(100, 0, Kitty([0; 16]));
+		Kitties::<Test>::insert(200, 1, Kitty([1; 16]));
+		StorageVersion::<DefaultInstance>::put(Releases::V1);
+		assert_eq!(<KittiesModule as KittyRegistry<u64>>::owner_of(&0), None);
+
+		KittiesModule::on_runtime_upgrade();
+		assert_eq!(KittiesModule::storage_version(), Releases::V2);
+		assert_eq!(<KittiesModule as KittyRegistry<u64>>::owner_of(&0), Some(100));
+		assert_eq!(<KittiesModule as KittyRegistry<u64>>::owner_of(&1), Some(200));
+		assert_eq!(KittiesModule::dna_kitty([1; 16]), Some(1));
+		assert_eq!(KittiesModule::unique_dna(|attempt| [attempt as u8; 16]), Some([2; 16]));
+		assert_eq!(KittiesModule::kitty_count(), 2);
+		assert_eq!(KittiesModule::trait_count(Gene::FurColour, 0), 1);
+		assert_eq!(KittiesModule::trait_count(Gene::Whiskers, 1), 1);
+		assert_eq!(KittiesModule::rarity(0), Some(6 * 200));
+		assert_ok!(<KittiesModule as KittyRegistry<u64>>::transfer(&1, &300));
+		assert!(Kitties::<Test>::contains_key(300, 1));
+	});
+}
+
+#[test]
+fn fuse_test() {
+    new_test_ext().execute_with(|| {
+		let me_id = 100;
+		let me = Origin::signed(me_id);
+		set_kitty_deposit(10);
+		// attack, defence and speed in bytes 8 to 10
+		assert_ok!(KittiesModule::mint(&me_id, &me_id, [0, 0, 0, 0, 0, 0, 0, 0, 15, 0, 3, 0, 0, 0, 0, 0]));
+		assert_ok!(KittiesModule::mint(&me_id, &me_id, [1, 1, 1, 1, 1, 1, 1, 1, 0, 15, 7, 1, 1, 1, 1, 1]));
+		assert_ok!(KittiesModule::mint(&200, &200, [2; 16]));
+
+		assert_noop!(KittiesModule::fuse(me.clone(), 0, 0), Error::CannotFuseSelf);
+		assert_noop!(KittiesModule::fuse(me.clone(), 0, 2), Error::KittyNotOwned);
+
+		assert_ok!(KittiesModule::fuse(me.clone(), 0, 1));
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::KittiesFused(me_id, 0, 1, 3))));
+		assert!(! Kitties::<Test>::contains_key(me_id, 0));
+		assert!(! Kitties::<Test>::contains_key(me_id, 1));
+		assert_eq!(KittiesModule::fused_from(3), Some((0, 1)));
+		assert_eq!(KittiesModule::parents(3), None);
+
+		// stronger stats of both, other bytes mixed
+		let dna = KittiesModule::kitties(me_id, 3).unwrap().0;
+		assert_eq!(Stats::from_dna(&dna), Stats { attack: 16, defence: 16, speed: 8 });
+		assert_eq!(dna, mix_dna(
+			bias_to_stronger(KittiesModule::random_value(&me_id), [0, 0, 0, 0, 0, 0, 0, 0, 15, 0, 3, 0, 0, 0, 0, 0], [1, 1, 1, 1, 1, 1, 1, 1, 0, 15, 7, 1, 1, 1, 1, 1]),
+			[0, 0, 0, 0, 0, 0, 0, 0, 15, 0, 3, 0, 0, 0, 0, 0],
+			[1, 1, 1, 1, 1, 1, 1, 1, 0, 15, 7, 1, 1, 1, 1, 1],
+		));
+
+		// one deposit left reserved, for the fused kitty
+		assert_eq!(Balances::reserved_balance(me_id), 10);
+		assert_eq!(KittiesModule::kitty_count(), 2);
+	});
+}
+
+#[test]
+fn rental_test() {
+    new_test_ext().execute_with(|| {
+		let owner_id = 100;
+		let owner = Origin::signed(owner_id);
+		let borrower_id = 200;
+		let borrower = Origin::signed(borrower_id);
+		assert_ok!(KittiesModule::mint(&owner_id, &owner_id, [0; 16]));  // male
+		assert_ok!(KittiesModule::mint(&borrower_id, &borrower_id, [1; 16]));  // female
+
+		assert_noop!(KittiesModule::lend(borrower.clone(), 0, borrower_id, 10, 20), Error::KittyNotOwned);
+		assert_noop!(KittiesModule::lend(owner.clone(), 0, owner_id, 10, 20), Error::CannotLendToSelf);
+		assert_ok!(KittiesModule::lend(owner.clone(), 0, borrower_id, 10, 20));
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::KittyLendOffered(owner_id, 0, borrower_id, 10, 20))));
+		assert_noop!(KittiesModule::borrow(Origin::signed(300), 0, 20), Error::LendOfferNotFound);
+		assert_noop!(KittiesModule::borrow(borrower.clone(), 0, 19), Error::KittyPriceTooLow);
+
+		assert_ok!(KittiesModule::borrow(borrower.clone(), 0, 20));
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::KittyBorrowed(owner_id, borrower_id, 0, 20, 11))));
+		assert_eq!(Balances::free_balance(owner_id), 120);
+		assert_eq!(Balances::free_balance(borrower_id), 180);
+		assert_eq!(KittiesModule::lend_offers(0), None);
+
+		// borrower breeds and battles, but can't sell, burn or re-lend
+		assert_ok!(KittiesModule::breed(borrower.clone(), 1, 0));
+		assert_noop!(KittiesModule::challenge(borrower.clone(), 0, 1, 0), Error::CannotFightSelf);
+		assert_ok!(KittiesModule::mint(&300, &300, [2; 16]));
+		assert_ok!(KittiesModule::challenge(borrower.clone(), 0, 2, 5));
+		assert_noop!(KittiesModule::transfer(borrower.clone(), 300, 0), Error::KittyNotOwned);
+		assert_noop!(KittiesModule::burn(borrower.clone(), 0), Error::KittyNotOwned);
+		assert_noop!(KittiesModule::lend(borrower.clone(), 0, 300, 10, 20), Error::KittyNotOwned);
+
+		// owner neither, until the kitty returns
+		assert_noop!(KittiesModule::transfer(owner.clone(), 300, 0), Error::KittyLent);
+		assert_noop!(KittiesModule::burn(owner.clone(), 0), Error::KittyLent);
+		assert_noop!(KittiesModule::lend(owner.clone(), 0, 300, 10, 20), Error::KittyLent);
+		assert_noop!(KittiesModule::challenge(owner.clone(), 0, 1, 0), Error::KittyLent);
+		assert_ok!(KittiesModule::set_price(owner.clone(), 0, Some(10)));
+		assert_noop!(KittiesModule::buy(Origin::signed(300), 0, 10), Error::KittyLent);
+
+		KittiesModule::on_initialize(11);
+		assert!(System::events().iter().any(|r| r.event == Event::kitties(crate::Event::<Test>::KittyReturned(owner_id, borrower_id, 0))));
+		assert_eq!(KittiesModule::rentals(0), None);
+		// the borrower's challenge ends with the rental
+		assert_eq!(KittiesModule::challenges(0), None);
+		assert_eq!(Balances::reserved_balance(borrower_id), 0);
+		assert_noop!(KittiesModule::challenge(borrower.clone(), 0, 1, 0), Error::KittyNotOwned);
+		assert_ok!(KittiesModule::transfer(owner.clone(), 300, 0));
+
+		// offers are withdrawn by their owner
+		assert_ok!(KittiesModule::lend(borrower.clone(), 1, owner_id, 10, 20));
+		assert_noop!(KittiesModule::cancel_lend(owner.clone(), 1), Error::KittyNotOwned);
+		assert_ok!(KittiesModule::cancel_lend(borrower.clone(), 1));
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::KittyLendCancelled(borrower_id, 1))));
+
+		// kitties locked as collateral since being offered can't be borrowed
+		assert_ok!(KittiesModule::lend(borrower.clone(), 1, owner_id, 10, 20));
+		assert_ok!(KittiesModule::request_loan(borrower.clone(), 1, 50, 5, 20));
+		assert_ok!(KittiesModule::fund_loan(Origin::signed(300), 1));
+		assert_noop!(KittiesModule::borrow(owner.clone(), 1, 20), Error::KittyCollateralised);
+
+		// sires lent out since being offered can't sire
+		assert_ok!(KittiesModule::offer_siring(Origin::signed(300), 2, 0, 100));
+		assert_ok!(KittiesModule::lend(Origin::signed(300), 2, owner_id, 10, 0));
+		assert_ok!(KittiesModule::borrow(owner.clone(), 2, 0));
+		assert_noop!(KittiesModule::breed_with_sire(borrower.clone(), 1, 2, 0), Error::KittyLent);
+	});
+}
+
+#[test]
+fn loan_test() {
+    new_test_ext().execute_with(|| {
+		let borrower_id = 100;
+		let borrower = Origin::signed(borrower_id);
+		let lender_id = 200;
+		let lender = Origin::signed(lender_id);
+		assert_ok!(KittiesModule::create(borrower.clone()));
+
+		assert_noop!(KittiesModule::request_loan(lender.clone(), 0, 50, 5, 10), Error::KittyNotOwned);
+		assert_noop!(KittiesModule::request_loan(borrower.clone(), 0, 50, 5, 1), Error::InvalidDeadline);
+		assert_ok!(KittiesModule::request_loan(borrower.clone(), 0, 50, 5, 10));
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::LoanRequested(borrower_id, 0, 50, 5, 10))));
+		assert_noop!(KittiesModule::request_loan(borrower.clone(), 0, 50, 5, 10), Error::LoanExists);
+		assert_noop!(KittiesModule::fund_loan(borrower.clone(), 0), Error::CannotLendToSelf);
+
+		// funded loan locks the kitty
+		assert_ok!(KittiesModule::fund_loan(lender.clone(), 0));
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::LoanFunded(lender_id, borrower_id, 0, 50))));
+		assert_eq!(Balances::free_balance(borrower_id), 150);
+		assert_noop!(KittiesModule::fund_loan(Origin::signed(300), 0), Error::LoanAlreadyFunded);
+		assert_noop!(KittiesModule::cancel_loan_request(borrower.clone(), 0), Error::LoanAlreadyFunded);
+		assert_noop!(KittiesModule::transfer(borrower.clone(), 300, 0), Error::KittyCollateralised);
+		assert_ok!(KittiesModule::set_price(borrower.clone(), 0, Some(10)));
+		assert_noop!(KittiesModule::buy(Origin::signed(300), 0, 10), Error::KittyCollateralised);
+		assert_noop!(KittiesModule::burn(borrower.clone(), 0), Error::KittyCollateralised);
+		assert_noop!(KittiesModule::claim_collateral(lender.clone(), 0), Error::LoanNotOverdue);
+
+		// repaid in time
+		assert_ok!(KittiesModule::repay_loan(borrower.clone(), 0));
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::LoanRepaid(borrower_id, lender_id, 0, 55))));
+		assert_eq!(Balances::free_balance(borrower_id), 95);
+		assert_eq!(Balances::free_balance(lender_id), 205);
+		assert_eq!(KittiesModule::loans(0), None);
+
+		// overdue, the lender claims the kitty
+		assert_ok!(KittiesModule::request_loan(borrower.clone(), 0, 50, 5, 10));
+		assert_ok!(KittiesModule::fund_loan(lender.clone(), 0));
+		System::set_block_number(11);
+		assert_noop!(KittiesModule::repay_loan(borrower.clone(), 0), Error::LoanOverdue);
+		assert_noop!(KittiesModule::claim_collateral(Origin::signed(300), 0), Error::LoanNotFound);
+		assert_ok!(KittiesModule::claim_collateral(lender.clone(), 0));
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::CollateralClaimed(lender_id, borrower_id, 0))));
+		assert!(Kitties::<Test>::contains_key(lender_id, 0));
+		assert_eq!(KittiesModule::loans(0), None);
+
+		// open requests are withdrawn by the borrower
+		assert_ok!(KittiesModule::request_loan(lender.clone(), 0, 50, 5, 20));
+		assert_ok!(KittiesModule::cancel_loan_request(lender.clone(), 0));
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::LoanRequestCancelled(lender_id, 0))));
+
+		// open requests don't follow the kitty to its new owner
+		assert_ok!(KittiesModule::request_loan(lender.clone(), 0, 50, 5, 20));
+		assert_ok!(KittiesModule::transfer(lender.clone(), borrower_id, 0));
+		assert_eq!(KittiesModule::loans(0), None);
+		assert_ok!(KittiesModule::request_loan(borrower.clone(), 0, 50, 5, 20));
+		assert_ok!(KittiesModule::set_price(borrower.clone(), 0, Some(10)));
+		assert_ok!(KittiesModule::buy(lender.clone(), 0, 10));
+		assert_eq!(KittiesModule::loans(0), None);
+
+		// kitties lent out since the request can't be funded
+		assert_ok!(KittiesModule::request_loan(lender.clone(), 0, 50, 5, 20));
+		assert_ok!(KittiesModule::lend(lender.clone(), 0, 300, 10, 0));
+		assert_ok!(KittiesModule::borrow(Origin::signed(300), 0, 0));
+		assert_noop!(KittiesModule::fund_loan(borrower.clone(), 0), Error::KittyLent);
+
+		// claims don't depend on collection settings changed since funding
+		assert_ok!(KittiesModule::create_collection(Origin::signed(300), b"Cats".to_vec(), None, Permill::zero(), true));
+		assert_ok!(KittiesModule::mint_into(Origin::signed(300), 0, borrower_id, false));
+		assert_ok!(KittiesModule::request_loan(borrower.clone(), 1, 50, 5, 20));
+		assert_ok!(KittiesModule::fund_loan(lender.clone(), 1));
+		assert_ok!(KittiesModule::set_collection_config(Origin::signed(300), 0, Permill::zero(), false));
+		System::set_block_number(21);
+		assert_ok!(KittiesModule::claim_collateral(lender.clone(), 1));
+		assert!(Kitties::<Test>::contains_key(lender_id, 1));
+	});
+}
+
+#[test]
+fn vault_test() {
+    new_test_ext().execute_with(|| {
+		let me_id = 100;
+		let me = Origin::signed(me_id);
+		let holder_id = 200;
+		let holder = Origin::signed(holder_id);
+		assert_ok!(KittiesModule::create(me.clone()));
+		let vault_id = KittiesModule::vault_account(0);
+
+		assert_noop!(KittiesModule::vault(me.clone(), 0, 0), Error::InvalidShares);
+		assert_noop!(KittiesModule::vault(holder.clone(), 0, 100), Error::KittyNotOwned);
+		assert_ok!(KittiesModule::vault(me.clone(), 0, 100));
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::KittyVaulted(me_id, 0, 100))));
+		assert!(Kitties::<Test>::contains_key(vault_id, 0));
+		assert_eq!(KittiesModule::shares(0, me_id), 100);
+
+		// the vault can't be moved out of but by redemption
+		assert_noop!(<KittiesModule as KittyRegistry<u64>>::transfer(&0, &me_id), Error::KittyVaulted);
+		assert_noop!(KittiesModule::transfer(me.clone(), holder_id, 0), Error::KittyNotOwned);
+
+		assert_noop!(KittiesModule::transfer_shares(me.clone(), 1, holder_id, 10), Error::VaultNotFound);
+		assert_noop!(KittiesModule::transfer_shares(me.clone(), 0, holder_id, 101), Error::InsufficientShares);
+		assert_ok!(KittiesModule::transfer_shares(me.clone(), 0, holder_id, 40));
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::SharesTransferred(me_id, holder_id, 0, 40))));
+		assert_eq!(KittiesModule::shares(0, me_id), 60);
+		assert_eq!(KittiesModule::shares(0, holder_id), 40);
+
+		// redemption needs all of the shares
+		assert_noop!(KittiesModule::redeem(holder.clone(), 0), Error::InsufficientShares);
+		assert_ok!(KittiesModule::transfer_shares(me.clone(), 0, holder_id, 60));
+		assert!(! Shares::<Test>::contains_key(0, me_id));
+		assert_ok!(KittiesModule::redeem(holder.clone(), 0));
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::KittyRedeemed(holder_id, 0))));
+		assert!(Kitties::<Test>::contains_key(holder_id, 0));
+		assert_eq!(KittiesModule::vaults(0), None);
+		assert_eq!(KittiesModule::shares(0, holder_id), 0);
+
+		// redemption doesn't depend on collection settings changed since vaulting
+		assert_ok!(KittiesModule::create_collection(me.clone(), b"Cats".to_vec(), None, Permill::zero(), true));
+		assert_ok!(KittiesModule::mint_into(me.clone(), 0, me_id, false));
+		assert_ok!(KittiesModule::vault(me.clone(), 1, 10));
+		assert_ok!(KittiesModule::set_collection_config(me.clone(), 0, Permill::zero(), false));
+		assert_ok!(KittiesModule::redeem(me.clone(), 1));
+		assert!(Kitties::<Test>::contains_key(me_id, 1));
+	});
+}
+
+#[test]
+fn soulbound_test() {
+    new_test_ext().execute_with(|| {
+		let creator_id = 100;
+		let creator = Origin::signed(creator_id);
+		let holder_id = 200;
+		let holder = Origin::signed(holder_id);
+		assert_ok!(KittiesModule::create_collection(creator.clone(), b"badges".to_vec(), None, Permill::zero(), true));
+		assert_ok!(KittiesModule::mint_into(creator.clone(), 0, holder_id, true));
+		assert_ok!(KittiesModule::mint_into(creator.clone(), 0, holder_id, false));
+		assert!(KittiesModule::soulbound(0));
+		assert!(! KittiesModule::soulbound(1));
+
+		assert_noop!(KittiesModule::transfer(holder.clone(), creator_id, 0), Error::KittySoulbound);
+		assert_noop!(KittiesModule::set_price(holder.clone(), 0, Some(10)), Error::KittySoulbound);
+		assert_noop!(KittiesModule::set_price_in_asset(holder.clone(), 0, 1, 10), Error::KittySoulbound);
+		assert_noop!(KittiesModule::approve(holder.clone(), 0, Some(creator_id)), Error::KittySoulbound);
+		assert_noop!(<KittiesModule as KittyRegistry<u64>>::transfer(&0, &creator_id), Error::KittySoulbound);
+		assert_ok!(KittiesModule::set_price(holder.clone(), 0, None));
+		assert_ok!(KittiesModule::transfer(holder.clone(), creator_id, 1));
+
+		// nor fused into a transferable kitty
+		assert_ok!(KittiesModule::mint_into(creator.clone(), 0, holder_id, false));
+		assert_noop!(KittiesModule::fuse(holder.clone(), 0, 2), Error::KittySoulbound);
+		assert_ok!(KittiesModule::set_collection_config(creator.clone(), 0, Permill::zero(), false));
+		assert_ok!(KittiesModule::mint_into(creator.clone(), 0, holder_id, false));
+		assert_noop!(KittiesModule::fuse(holder.clone(), 2, 3), Error::CollectionNotTransferable);
+
+		// burning is still allowed
+		assert_ok!(KittiesModule::burn(holder.clone(), 0));
+		assert!(! KittiesModule::soulbound(0));
+	});
+}
+
+#[test]
+fn batch_test() {
+    new_test_ext().execute_with(|| {
+		let me_id = 100;
+		let me = Origin::signed(me_id);
+		assert_ok!(KittiesModule::mint(&me_id, &me_id, [0; 16]));  // male
+		assert_ok!(KittiesModule::mint(&me_id, &me_id, [1; 16]));  // female
+		assert_ok!(KittiesModule::mint(&me_id, &me_id, [3; 16]));  // female
+		assert_ok!(KittiesModule::mint(&200, &200, [2; 16]));
+
+		assert_noop!(KittiesModule::transfer_batch(me.clone(), vec![(200, 0), (200, 1), (200, 2), (200, 3)], BatchMode::BestEffort), Error::BatchTooLarge);
+
+		// all or nothing, kitty 3 isn't owned
+		assert_noop!(KittiesModule::set_price_batch(me.clone(), vec![(0, Some(10)), (3, Some(10))], BatchMode::AllOrNothing), Error::KittyNotOwned);
+
+		// best effort, failing items are skipped
+		assert_ok!(KittiesModule::set_price_batch(me.clone(), vec![(0, Some(10)), (3, Some(10)), (1, Some(20))], BatchMode::BestEffort));
+		assert_eq!(KittiesModule::prices(0), Some(10));
+		assert_eq!(KittiesModule::prices(1), Some(20));
+		assert!(System::events().iter().any(|r| r.event == Event::kitties(crate::Event::<Test>::BatchItemCompleted(1, Err(Error::KittyNotOwned.into())))));
+		assert_eq!(last_event(), Some(Event::kitties(crate::Event::<Test>::BatchItemCompleted(2, Ok(())))));
+
+		assert_ok!(KittiesModule::breed_batch(me.clone(), vec![(1, 0), (2, 0), (1, 2)], BatchMode::BestEffort));
+		assert!(KittiesModule::pregnancies(1).is_some());
+		assert!(KittiesModule::pregnancies(2).is_some());
+		assert!(System::events().iter().any(|r| r.event == Event::kitties(crate::Event::<Test>::BatchItemCompleted(2, Err(Error::KittiesBredFromSameGenderCouple.into())))));
+
+		assert_ok!(KittiesModule::transfer_batch(me.clone(), vec![(300, 0), (300, 1)], BatchMode::AllOrNothing));
+		assert!(Kitties::<Test>::contains_key(300, 0));
+		assert!(Kitties::<Test>::contains_key(300, 1));
+	});
+}
+
+#[test]
+fn burn_test() {
+    new_test_ext().execute_with(|| {
+		let me_id = 100;
+		let me = Origin::signed(me_id);
+		set_kitty_deposit(10);
+
+		assert_ok!(KittiesModule::create(me.clone()));
+		assert_eq!(Balances::reserved_balance(me_id), 10);
+		assert_eq!(KittiesModule::deposits(0), Some((me_id, 10)));
+		assert_ok!(KittiesModule::set_price(me.clone(), 0, Some(50)));
+
+		assert_noop!(KittiesModule::burn(Origin::signed(200), 0), Error::KittyNotOwned);
+
+		assert_ok!(KittiesModule::burn(me.clone(), 0));
+		assert_eq!(last_event(), Some(Event::kitties(RawEvent::KittyBurned(me_id, 0))));
+		assert!(! Kitties::<Test>::contains_key(me_id, 0));
+		assert_eq!(Prices::<Test>::get(0), None);
+		assert_eq!(KittiesModule::deposits(0), None);
+		assert_eq!(Balances::reserved_balance(me_id), 0);
+		assert_eq!(Balances::free_balance(me_id), 100);
+
+		// can't afford the deposit
+		set_kitty_deposit(1000);
+		assert_noop!(KittiesModule::create(me.clone()), pallet_balances::Error::<Test, _>::InsufficientBalance);
+	});
+}
+
+#[test]
+fn instances_test() {
+    new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(100)));
+		assert_ok!(KittiesModule::create(Origin::signed(100)));
+		assert_ok!(Puppies::create(Origin::signed(200)));
+
+		// separate id spaces and storage
+		assert_eq!(KittiesModule::next_kitty_id(), 2);
+		assert_eq!(Puppies::next_kitty_id(), 1);
+		assert!(Kitties::<Test>::contains_key(100, 0));
+		assert!(! Kitties::<Test, Instance1>::contains_key(100, 0));
+		assert!(Kitties::<Test, Instance1>::contains_key(200, 0));
+		assert_noop!(Puppies::transfer(Origin::signed(100), 300, 1), crate::Error::<Test, Instance1>::KittyNotOwned);
+	});
+}
+
+#[test]
+fn genes_test() {
+	let dna = [0, 9, 6, 3, 4, 7, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+	assert_eq!(Gene::FurColour.decode(&dna), 1);
+	assert_eq!(Gene::Pattern.decode(&dna), 2);
+	assert_eq!(Gene::Whiskers.decode(&dna), 1);
+	assert_eq!(genes::decode(&dna), [
+		(Gene::FurColour, 1),
+		(Gene::Pattern, 2),
+		(Gene::EyeColour, 3),
+		(Gene::EyeShape, 0),
+		(Gene::Tail, 3),
+		(Gene::Whiskers, 1),
+	]);
+}
+
+#[test]
+fn fight_test() {
+	let strong = Stats { attack: 16, defence: 16, speed: 16 };
+	let weak = Stats { attack: 1, defence: 1, speed: 1 };
+
+	// the faster strikes first, the weak can only graze the strong
+	let outcome = battle::fight(weak, strong, [7; 16]);
+	assert!(! outcome.challenger_wins);
+	assert_eq!(outcome.strikes[0], Strike { by_challenger: false, damage: 38, hit_points: 62 });
+	assert_eq!(outcome.strikes[1], Strike { by_challenger: true, damage: 1, hit_points: 99 });
+	assert_eq!(outcome.strikes.len(), 5);
+	assert_eq!(outcome.strikes[4].hit_points, 0);
+
+	// undecided after all rounds, hit point ties go to the challenger
+	let outcome = battle::fight(weak, weak, [0; 16]);
+	assert_eq!(outcome.strikes.len(), 2 * battle::MAX_ROUNDS);
+	assert!(outcome.challenger_wins);
+}
+
 #[test]
 fn gender() {
     assert_eq!(Kitty([0; 16]).get_gender(), Gender::Male);
@@ -0,0 +1,62 @@
+//! Abstractions for other pallets (auctions, lending, games...) to depend on,
+//! rather than on pallet-kitties' storage.
+
+use frame_support::dispatch::DispatchResult;
+use frame_support::weights::Weight;
+use crate::Kitty;
+
+/// Registry of kitties and their owners, in the spirit of non-fungible tokens
+pub trait KittyRegistry<AccountId> {
+	/// Kitty identifier
+	type KittyIndex;
+
+	/// Owner of the kitty, `None` if the kitty doesn't exist
+	fn owner_of(kitty_id: &Self::KittyIndex) -> Option<AccountId>;
+
+	/// The kitty's DNA, `None` if the kitty doesn't exist
+	fn kitty(kitty_id: &Self::KittyIndex) -> Option<Kitty>;
+
+	/// Transfer the kitty from its current owner to `new_owner`
+	fn transfer(kitty_id: &Self::KittyIndex, new_owner: &AccountId) -> DispatchResult;
+}
+
+/// Hook on kitty ownership changes: create, breed, transfer, buy and burn.
+/// `from` is `None` when a kitty is minted, `to` is `None` when it's burned
+pub trait OnKittyTransfer<AccountId, KittyIndex> {
+	/// Called before the change, an error vetoes it, eg. for kitties locked by staking or lending
+	fn can_transfer(from: Option<&AccountId>, to: Option<&AccountId>, kitty_id: &KittyIndex) -> DispatchResult;
+
+	/// Called after the change
+	fn on_transfer(from: Option<&AccountId>, to: Option<&AccountId>, kitty_id: &KittyIndex);
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<AccountId, KittyIndex> OnKittyTransfer<AccountId, KittyIndex> for Tuple {
+	fn can_transfer(from: Option<&AccountId>, to: Option<&AccountId>, kitty_id: &KittyIndex) -> DispatchResult {
+		for_tuples!( #( Tuple::can_transfer(from, to, kitty_id)?; )* );
+		Ok(())
+	}
+
+	fn on_transfer(from: Option<&AccountId>, to: Option<&AccountId>, kitty_id: &KittyIndex) {
+		for_tuples!( #( Tuple::on_transfer(from, to, kitty_id); )* );
+	}
+}
+
+/// Fungible assets, other than the native currency, that kitties can be priced in
+pub trait FungibleAssets<AccountId> {
+	/// Asset identifier
+	type AssetId;
+
+	/// Asset balance
+	type Balance;
+
+	/// Transfer `amount` of the asset from `source` to `dest`. Callers only ever pass the account
+	/// that signed the call being settled as `source`
+	fn transfer(asset_id: Self::AssetId, source: &AccountId, dest: &AccountId, amount: Self::Balance) -> DispatchResult;
+
+	/// Weight of a transfer, charged by the calls settling in assets
+	fn transfer_weight() -> Weight;
+
+	/// Create the asset, owned by `owner`. Sets up assets at genesis
+	fn create(asset_id: Self::AssetId, owner: &AccountId, min_balance: Self::Balance) -> DispatchResult;
+}
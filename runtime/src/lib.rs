@@ -9,7 +9,7 @@ include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 use sp_std::prelude::*;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
-	ApplyExtrinsicResult, generic, create_runtime_str, impl_opaque_keys, MultiSignature,
+	ApplyExtrinsicResult, DispatchResult, ModuleId, generic, create_runtime_str, impl_opaque_keys, MultiSignature,
 	transaction_validity::{TransactionValidity, TransactionSource},
 };
 use sp_runtime::traits::{
@@ -31,13 +31,15 @@ pub use pallet_balances::Call as BalancesCall;
 pub use sp_runtime::{Permill, Perbill};
 pub use frame_support::{
 	construct_runtime, parameter_types, StorageValue,
-	traits::{KeyOwnerProofSystem, Randomness},
+	traits::{KeyOwnerProofSystem, Randomness, UnfilteredDispatchable},
 	weights::{
 		Weight, IdentityFee,
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
 	},
 };
 use pallet_transaction_payment::CurrencyAdapter;
+use pallet_assets::WeightInfo as _;
+use frame_system::EnsureRoot;
 
 /// Import the template pallet.
 pub use pallet_template;
@@ -65,6 +67,9 @@ pub type Index = u32;
 /// A hash of some data used by the chain.
 pub type Hash = sp_core::H256;
 
+/// Index of a kitty.
+pub type KittyIndex = u32;
+
 /// Digest item type.
 pub type DigestItem = generic::DigestItem<Hash>;
 
@@ -103,7 +108,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 100,
+	spec_version: 101,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
 	transaction_version: 1,
@@ -232,8 +237,11 @@ impl pallet_timestamp::Config for Runtime {
 	type WeightInfo = ();
 }
 
+/// Existential deposit of the native currency.
+pub const EXISTENTIAL_DEPOSIT: Balance = 500;
+
 parameter_types! {
-	pub const ExistentialDeposit: u128 = 500;
+	pub const ExistentialDeposit: u128 = EXISTENTIAL_DEPOSIT;
 	pub const MaxLocks: u32 = 50;
 }
 
@@ -265,16 +273,142 @@ impl pallet_sudo::Config for Runtime {
 	type Call = Call;
 }
 
+parameter_types! {
+	pub const AssetDepositBase: Balance = 100 * EXISTENTIAL_DEPOSIT;
+	pub const AssetDepositPerZombie: Balance = EXISTENTIAL_DEPOSIT;
+	pub const StringLimit: u32 = 50;
+	pub const MetadataDepositBase: Balance = 10 * EXISTENTIAL_DEPOSIT;
+	pub const MetadataDepositPerByte: Balance = EXISTENTIAL_DEPOSIT;
+}
+
+/// Identifier of an asset in pallet-assets.
+pub type AssetId = u32;
+
+impl pallet_assets::Config for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type AssetId = AssetId;
+	type Currency = Balances;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type AssetDepositBase = AssetDepositBase;
+	type AssetDepositPerZombie = AssetDepositPerZombie;
+	type StringLimit = StringLimit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
+}
+
 /// Configure the pallet-template in pallets/template.
 impl pallet_template::Config for Runtime {
 	type Event = Event;
 }
 
+parameter_types! {
+	pub const GestationPeriod: BlockNumber = MINUTES;
+	pub const KittyDeposit: Balance = 1_000;
+	pub const KittyKeyLimit: u32 = 32;
+	pub const KittyValueLimit: u32 = 256;
+	pub const KittyMaxAttributes: u32 = 16;
+	pub const KittyAttributeDepositPerByte: Balance = 10;
+	pub const KittyNameLimit: u32 = 32;
+	pub const KittyNameDeposit: Balance = 1_000;
+	pub const KittyUriLimit: u32 = 128;
+	pub const MaxDnaRetries: u32 = 5;
+	pub const KittiesModuleId: ModuleId = ModuleId(*b"py/kitty");
+	pub const KittyMaxBatchSize: u32 = 50;
+	pub const MaxBracketSize: u32 = 64;
+	pub const MaxActiveTournaments: u32 = 4;
+}
+
+/// Accounts able to hold assets created at genesis without an existential deposit.
+pub const GENESIS_ASSET_MAX_ZOMBIES: u32 = 1_000;
+
+/// Settles kitty purchases priced in pallet-assets assets.
+/// pallet-assets 3.0 has no public transfer, its transfer call is dispatched instead, only ever
+/// on behalf of the buyer who signed the purchase.
+pub struct KittyAssets;
+
+impl pallet_kitties::FungibleAssets<AccountId> for KittyAssets {
+	type AssetId = AssetId;
+	type Balance = Balance;
+
+	fn transfer(asset_id: AssetId, source: &AccountId, dest: &AccountId, amount: Balance) -> DispatchResult {
+		pallet_assets::Call::<Runtime>::transfer(asset_id, dest.clone().into(), amount)
+			.dispatch_bypass_filter(Origin::signed(source.clone()))
+			.map(|_| ())
+			.map_err(|e| e.error)
+	}
+
+	fn transfer_weight() -> Weight {
+		<Runtime as pallet_assets::Config>::WeightInfo::transfer()
+	}
+
+	fn create(asset_id: AssetId, owner: &AccountId, min_balance: Balance) -> DispatchResult {
+		pallet_assets::Call::<Runtime>::force_create(asset_id, owner.clone().into(), GENESIS_ASSET_MAX_ZOMBIES, min_balance)
+			.dispatch_bypass_filter(Origin::root())
+			.map(|_| ())
+			.map_err(|e| e.error)
+	}
+}
+
 impl pallet_kitties::Config for Runtime {
 	type Event = Event;
-	type KittyIndex = u32;
+	type KittyIndex = KittyIndex;
 	type Currency = Balances;
 	type Randomness = RandomnessCollectiveFlip;
+	type GestationPeriod = GestationPeriod;
+	type KittyDeposit = KittyDeposit;
+	type OnTransfer = ();
+	type AssetId = AssetId;
+	type Assets = KittyAssets;
+	type CollectionId = u32;
+	type StringLimit = StringLimit;
+	type KeyLimit = KittyKeyLimit;
+	type ValueLimit = KittyValueLimit;
+	type MaxAttributes = KittyMaxAttributes;
+	type AttributeDepositPerByte = KittyAttributeDepositPerByte;
+	type NameLimit = KittyNameLimit;
+	type NameDeposit = KittyNameDeposit;
+	type UriLimit = KittyUriLimit;
+	type MaxDnaRetries = MaxDnaRetries;
+	type TournamentOrigin = EnsureRoot<AccountId>;
+	type MaxBracketSize = MaxBracketSize;
+	type MaxActiveTournaments = MaxActiveTournaments;
+	type ModuleId = KittiesModuleId;
+	type MaxBatchSize = KittyMaxBatchSize;
+}
+
+parameter_types! {
+	pub const PuppyGestationPeriod: BlockNumber = 2 * MINUTES;
+	pub const PuppiesModuleId: ModuleId = ModuleId(*b"py/puppy");
+}
+
+/// Puppies, a second independent registry running on pallet-kitties.
+impl pallet_kitties::Config<pallet_kitties::Instance1> for Runtime {
+	type Event = Event;
+	type KittyIndex = KittyIndex;
+	type Currency = Balances;
+	type Randomness = RandomnessCollectiveFlip;
+	type GestationPeriod = PuppyGestationPeriod;
+	type KittyDeposit = KittyDeposit;
+	type OnTransfer = ();
+	type AssetId = AssetId;
+	type Assets = KittyAssets;
+	type CollectionId = u32;
+	type StringLimit = StringLimit;
+	type KeyLimit = KittyKeyLimit;
+	type ValueLimit = KittyValueLimit;
+	type MaxAttributes = KittyMaxAttributes;
+	type AttributeDepositPerByte = KittyAttributeDepositPerByte;
+	type NameLimit = KittyNameLimit;
+	type NameDeposit = KittyNameDeposit;
+	type UriLimit = KittyUriLimit;
+	type MaxDnaRetries = MaxDnaRetries;
+	type TournamentOrigin = EnsureRoot<AccountId>;
+	type MaxBracketSize = MaxBracketSize;
+	type MaxActiveTournaments = MaxActiveTournaments;
+	type ModuleId = PuppiesModuleId;
+	type MaxBatchSize = KittyMaxBatchSize;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -295,7 +429,10 @@ construct_runtime!(
 		// Include the custom logic from the pallet-template in the runtime.
 		TemplateModule: pallet_template::{Module, Call, Storage, Event<T>},
 		// Substrate Kitties module
-		Kitties: pallet_kitties::{Module, Storage, Call, Event<T>},
+		Assets: pallet_assets::{Module, Call, Storage, Event<T>},
+		Kitties: pallet_kitties::{Module, Storage, Call, Config<T>, Event<T>},
+		// Substrate Kitties module, instance for puppies
+		Puppies: pallet_kitties::<Instance1>::{Module, Storage, Call, Config<T>, Event<T>},
 	}
 );
 
@@ -447,6 +584,16 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_kitties_rpc_runtime_api::KittiesApi<Block, AccountId, KittyIndex> for Runtime {
+		fn kitty_details(kitty_id: KittyIndex) -> Option<pallet_kitties::KittyDetails<AccountId>> {
+			Kitties::kitty_details(kitty_id)
+		}
+
+		fn kitty_rarity(kitty_id: KittyIndex) -> Option<u32> {
+			Kitties::rarity(kitty_id)
+		}
+	}
+
 	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<Block, Balance> for Runtime {
 		fn query_info(
 			uxt: <Block as BlockT>::Extrinsic,